@@ -0,0 +1,86 @@
+//! Benchmarks of section and key storage on documents with 100k keys
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use tini::Ini;
+
+const KEYS: usize = 100_000;
+
+/// Document with one section of `KEYS` keys
+fn wide_document() -> Ini {
+    let mut ini = Ini::new();
+    for i in 0..KEYS {
+        ini.set("section", &format!("key{}", i), i);
+    }
+    ini
+}
+
+/// Document with `KEYS` sections of one key
+fn long_document() -> Ini {
+    let mut ini = Ini::new();
+    for i in 0..KEYS {
+        ini.set(&format!("section{}", i), "key", i);
+    }
+    ini
+}
+
+fn insert(c: &mut Criterion) {
+    c.bench_function("insert 100k keys", |b| b.iter(wide_document));
+    c.bench_function("insert 100k sections", |b| b.iter(long_document));
+}
+
+fn get(c: &mut Criterion) {
+    let ini = wide_document();
+    let keys: Vec<String> = (0..KEYS).map(|i| format!("key{}", i)).collect();
+    c.bench_function("get 100k keys", |b| {
+        b.iter(|| keys.iter().filter_map(|k| ini.get::<usize>("section", k)).sum::<usize>())
+    });
+}
+
+fn remove(c: &mut Criterion) {
+    let keys: Vec<String> = (0..KEYS).step_by(2).map(|i| format!("key{}", i)).collect();
+    c.bench_function("remove 50k of 100k keys", |b| {
+        b.iter_batched(
+            wide_document,
+            |mut ini| {
+                for key in &keys {
+                    ini.remove_key("section", key);
+                }
+                ini
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    let sections: Vec<String> = (0..KEYS).step_by(2).map(|i| format!("section{}", i)).collect();
+    c.bench_function("remove 50k of 100k sections", |b| {
+        b.iter_batched(
+            long_document,
+            |mut ini| {
+                for section in &sections {
+                    ini.remove_section(section);
+                }
+                ini
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn iterate(c: &mut Criterion) {
+    let mut ini = wide_document();
+    c.bench_function("iter 100k keys", |b| b.iter(|| ini.section_iter("section").count()));
+    c.bench_function("iter_mut 100k keys", |b| {
+        b.iter(|| {
+            for (_, section) in ini.iter_mut() {
+                for (_, value) in section.iter_mut() {
+                    black_box(value);
+                }
+            }
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = insert, get, remove, iterate
+}
+criterion_main!(benches);
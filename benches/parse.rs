@@ -0,0 +1,40 @@
+//! Benchmarks of parsing documents with 1k, 100k and 1M lines
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use tini::{Ini, IniRef};
+
+/// Document of `lines` lines: sections of 10 keys with comments and blank lines between them
+fn document(lines: usize) -> String {
+    let mut text = String::new();
+    let mut section = 0;
+    let mut written = 0;
+    while written < lines {
+        text.push_str(&format!("; section number {}\n[section{}]\n", section, section));
+        for key in 0..10 {
+            text.push_str(&format!("key{} = value of key {} ; inline comment\n", key, key));
+        }
+        text.push('\n');
+        section += 1;
+        written += 13;
+    }
+    text
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    for (name, lines) in [("1k", 1_000), ("100k", 100_000), ("1M", 1_000_000)] {
+        let text = document(lines);
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_function(format!("from_string {} lines", name), |b| {
+            b.iter(|| Ini::from_string(black_box(text.as_str())).unwrap())
+        });
+        group.bench_function(format!("from_reader {} lines", name), |b| {
+            b.iter(|| Ini::from_reader(&mut black_box(text.as_bytes())).unwrap())
+        });
+        group.bench_function(format!("IniRef {} lines", name), |b| b.iter(|| IniRef::parse(black_box(&text)).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
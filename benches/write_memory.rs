@@ -0,0 +1,71 @@
+//! Peak memory of writing a document with 1M keys: streaming `to_writer` against formatting the whole
+//! text first, like `to_writer` did before, and collecting lines before joining them, like `Display` did
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use tini::Ini;
+
+const SECTIONS: usize = 10_000;
+const KEYS: usize = 100;
+
+/// Allocator which tracks current and peak size of allocated memory
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Run `f` and print its time and peak memory above memory in use before the call
+fn measure<F: FnOnce()>(name: &str, f: F) {
+    let base = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(base, Ordering::SeqCst);
+    let start = Instant::now();
+    f();
+    let elapsed = start.elapsed();
+    let peak = PEAK.load(Ordering::SeqCst) - base;
+    println!("{:<24} peak {:>10.2} MiB, {:>8.2?}", name, peak as f64 / (1 << 20) as f64, elapsed);
+}
+
+fn main() {
+    let mut ini = Ini::new();
+    for s in 0..SECTIONS {
+        let section = format!("section{}", s);
+        for k in 0..KEYS {
+            ini.set(&section, &format!("key{}", k), format!("value of key {} in section {}", k, s));
+        }
+    }
+    let size = ini.to_string().len();
+    println!("document: {} keys, {:.2} MiB of text", SECTIONS * KEYS, size as f64 / (1 << 20) as f64);
+
+    measure("lines + join", || {
+        let text = ini.to_string();
+        let lines: Vec<String> = text.lines().map(str::to_owned).collect();
+        drop(text);
+        io::sink().write_all(lines.join("\n").as_bytes()).unwrap();
+    });
+    measure("to_string + write_all", || {
+        io::sink().write_all(ini.to_string().as_bytes()).unwrap();
+    });
+    measure("to_writer", || {
+        ini.to_writer(&mut io::sink()).unwrap();
+    });
+}
@@ -0,0 +1,229 @@
+//! Borrowed document module
+//!
+//! Contains [IniRef] and [SectionRef] which are parsed from string without copying section names,
+//! keys and values
+use crate::error::Error;
+use crate::ordered_hashmap::{self, OrderedHashMap};
+use crate::parser::{parse_line, Parsed};
+use crate::value::parse_list;
+use crate::{FromIniValue, Ini};
+
+/// Read-only ini document which borrows section names, keys and values from parsed text
+///
+/// Parsing doesn't copy the text, so it suits many short-lived documents. Only default
+/// [ParseOptions](crate::ParseOptions) are supported and comments are skipped,
+/// use [`to_owned()`](IniRef::to_owned) to get editable [Ini].
+///
+/// # Example
+/// ```
+/// # use tini::IniRef;
+/// let text = String::from("[server]\nhost = localhost\nports = 80, 443");
+/// let conf = IniRef::parse(&text).unwrap();
+///
+/// assert_eq!(conf.get_raw("server", "host"), Some("localhost"));
+/// assert_eq!(conf.get_vec::<u16>("server", "ports"), Some(vec![80, 443]));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IniRef<'a> {
+    document: OrderedHashMap<&'a str, SectionRef<'a>>,
+    empty_section: SectionRef<'a>,
+}
+
+impl<'a> IniRef<'a> {
+    /// Parse `text` into borrowed document
+    ///
+    /// Duplicate sections and keys are merged like in [Ini::from_string].
+    ///
+    /// # Errors
+    /// This function will return an [Error] if `text` cannot be parsed
+    pub fn parse(text: &'a str) -> Result<IniRef<'a>, Error> {
+        let mut result = IniRef::default();
+        let mut section = "";
+        for (index, line) in text.lines().enumerate() {
+            match parse_line(line, index + 1)? {
+                Parsed::Section(name) => section = name,
+                Parsed::Value(key, value) => {
+                    result.document.entry(section).or_default().inner.insert(key, value);
+                }
+                Parsed::Comment(_) | Parsed::Empty => (),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Get scalar value of `key` in `section`, see [Ini::get]
+    pub fn get<T>(&self, section: &str, key: &str) -> Option<T>
+    where
+        T: FromIniValue,
+    {
+        self.get_raw(section, key).and_then(|x| T::from_ini_value(x).ok())
+    }
+
+    /// Get raw value of `key` in `section` borrowed from parsed text
+    pub fn get_raw(&self, section: &str, key: &str) -> Option<&'a str> {
+        self.document.get(section).and_then(|s| s.get_raw(key))
+    }
+
+    /// Get vector value of `key` in `section`, see [Ini::get_vec]
+    pub fn get_vec<T>(&self, section: &str, key: &str) -> Option<Vec<T>>
+    where
+        T: FromIniValue,
+    {
+        self.get_vec_with_sep(section, key, ",")
+    }
+
+    /// Get vector value of `key` in `section` separated by `sep`, see [Ini::get_vec_with_sep]
+    pub fn get_vec_with_sep<T>(&self, section: &str, key: &str, sep: &str) -> Option<Vec<T>>
+    where
+        T: FromIniValue,
+    {
+        self.get_raw(section, key).and_then(|x| parse_list(x, sep).ok())
+    }
+
+    /// Get `section` if it exists
+    pub fn get_section(&self, section: &str) -> Option<&SectionRef<'a>> {
+        self.document.get(section)
+    }
+
+    /// Iterate over key-value pairs of `section` in order of appearance,
+    /// iterator is empty if there is no such section
+    pub fn section_iter(&self, section: &str) -> SectionRefIter<'_, 'a> {
+        self.document.get(section).unwrap_or(&self.empty_section).iter()
+    }
+
+    /// Iterate over sections in order of appearance
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::IniRef;
+    /// let conf = IniRef::parse("[b]\nx = 1\n[a]\ny = 2").unwrap();
+    ///
+    /// let names: Vec<&str> = conf.iter().map(|(name, _)| name).collect();
+    /// assert_eq!(names, ["b", "a"]);
+    /// ```
+    pub fn iter(&self) -> IniRefIter<'_, 'a> {
+        IniRefIter { iter: self.document.iter() }
+    }
+
+    /// Copy document into [Ini]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, IniRef};
+    /// let text = "[a]\nx = 1\n[a]\ny = 2";
+    /// let mut conf = IniRef::parse(text).unwrap().to_owned();
+    /// conf.set("a", "z", 3);
+    ///
+    /// assert_eq!(conf.to_string(), "[a]\nx = 1\ny = 2\nz = 3\n");
+    /// ```
+    pub fn to_owned(&self) -> Ini {
+        let mut ini = Ini::new();
+        for (name, section) in self.iter() {
+            let target = ini.section_or_insert(name.to_owned());
+            for (key, value) in section.iter() {
+                target.insert(key.to_owned(), value.to_owned());
+            }
+        }
+        ini
+    }
+}
+
+/// Section of [IniRef]
+#[derive(Debug, Clone, Default)]
+pub struct SectionRef<'a> {
+    inner: OrderedHashMap<&'a str, &'a str>,
+}
+
+impl<'a> SectionRef<'a> {
+    /// Get scalar value of `key`, see [Section::get](crate::Section::get)
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: FromIniValue,
+    {
+        self.get_raw(key).and_then(|x| T::from_ini_value(x).ok())
+    }
+
+    /// Get raw value of `key` borrowed from parsed text
+    pub fn get_raw(&self, key: &str) -> Option<&'a str> {
+        self.inner.get(key).copied()
+    }
+
+    /// Get vector value of `key`, see [Section::get_vec](crate::Section::get_vec)
+    pub fn get_vec<T>(&self, key: &str) -> Option<Vec<T>>
+    where
+        T: FromIniValue,
+    {
+        self.get_vec_with_sep(key, ",")
+    }
+
+    /// Get vector value of `key` separated by `sep`
+    pub fn get_vec_with_sep<T>(&self, key: &str, sep: &str) -> Option<Vec<T>>
+    where
+        T: FromIniValue,
+    {
+        self.get_raw(key).and_then(|x| parse_list(x, sep).ok())
+    }
+
+    /// Number of keys in section
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if section contains no keys
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Iterate over key-value pairs in order of appearance
+    pub fn iter(&self) -> SectionRefIter<'_, 'a> {
+        SectionRefIter { iter: self.inner.iter() }
+    }
+}
+
+/// An iterator over the sections of [IniRef]
+pub struct IniRefIter<'s, 'a> {
+    #[doc(hidden)]
+    iter: ordered_hashmap::Iter<'s, &'a str, SectionRef<'a>>,
+}
+
+impl<'s, 'a> Iterator for IniRefIter<'s, 'a> {
+    type Item = (&'a str, &'s SectionRef<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(name, section)| (*name, section))
+    }
+}
+
+/// An iterator over the key-value pairs of [SectionRef]
+pub struct SectionRefIter<'s, 'a> {
+    #[doc(hidden)]
+    iter: ordered_hashmap::Iter<'s, &'a str, &'a str>,
+}
+
+impl<'s, 'a> Iterator for SectionRefIter<'s, 'a> {
+    type Item = (&'a str, &'a str);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(key, value)| (*key, *value))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn same_as_owned() -> Result<(), Error> {
+        let text = "; comment\nglobal = 1\n[a]\nx = 1 ; inline\ny = 2\n[b]\nz = 3\n[a]\nx = 4";
+        let borrowed = IniRef::parse(text)?;
+        let owned = Ini::from_string(text)?;
+        assert_eq!(borrowed.to_owned(), owned);
+        assert_eq!(borrowed.get::<u8>("a", "x"), Some(4));
+        assert_eq!(borrowed.section_iter("a").collect::<Vec<_>>(), [("x", "4"), ("y", "2")]);
+        assert_eq!(borrowed.section_iter("missing").count(), 0);
+        assert!(IniRef::parse("[a]\nbroken").is_err());
+        Ok(())
+    }
+}
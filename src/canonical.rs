@@ -0,0 +1,162 @@
+//! Canonical form module
+//!
+//! Contains [SortOrder] rules, `normalize` routine for values in canonical form
+//! and `Fnv1a` hasher which is used for stable content hashes
+use crate::list;
+use crate::parser::ArrayKind;
+use std::cmp::Ordering;
+
+/// Rule for sorting sections and keys, see [Ini::canonicalize](crate::Ini::canonicalize)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// keep order of appearance
+    Keep,
+    /// compare names as strings: `key1`, `key10`, `key2`
+    #[default]
+    Alphabetical,
+    /// compare digit runs as numbers: `key1`, `key2`, `key10`
+    Natural,
+}
+
+impl SortOrder {
+    /// Compare names `a` and `b` using this rule
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::SortOrder;
+    /// use std::cmp::Ordering;
+    ///
+    /// assert_eq!(SortOrder::Alphabetical.compare("key10", "key2"), Ordering::Less);
+    /// assert_eq!(SortOrder::Natural.compare("key10", "key2"), Ordering::Greater);
+    /// assert_eq!(SortOrder::Keep.compare("b", "a"), Ordering::Equal);
+    /// ```
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        match self {
+            SortOrder::Keep => Ordering::Equal,
+            SortOrder::Alphabetical => a.cmp(b),
+            SortOrder::Natural => natural(a, b),
+        }
+    }
+}
+
+/// Compare strings with digit runs compared by numeric value, leading zeros break ties
+fn natural(mut a: &str, mut b: &str) -> Ordering {
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (number_a, rest_a) = a.split_at(digits(a));
+                let (number_b, rest_b) = b.split_at(digits(b));
+                let (value_a, value_b) = (number_a.trim_start_matches('0'), number_b.trim_start_matches('0'));
+                let ordering = value_a
+                    .len()
+                    .cmp(&value_b.len())
+                    .then_with(|| value_a.cmp(value_b))
+                    .then_with(|| number_a.len().cmp(&number_b.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a = rest_a;
+                b = rest_b;
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                a = &a[x.len_utf8()..];
+                b = &b[y.len_utf8()..];
+            }
+        }
+    }
+}
+
+/// Canonical spelling of boolean words, other values are returned as is
+fn boolean(value: &str) -> &str {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" => "true",
+        "false" | "no" | "off" => "false",
+        _ => value,
+    }
+}
+
+/// Canonical form of `value`: boolean words are written as `true`/`false` and every value
+/// with unquoted `,` is a list which is joined with `", "`, maps of php-style array of `kind`
+/// are joined with `":"` between key and value
+pub fn normalize(value: &str, kind: Option<ArrayKind>) -> String {
+    if let Some(items) = kind.filter(|&kind| kind == ArrayKind::Map).and_then(|kind| crate::array_items(kind, value)) {
+        return list::join_map(items.iter().map(|(k, v)| (k, boolean(v))), ":", ", ");
+    }
+    if value.contains(',') {
+        if let Ok(elements) = list::split(value, ",") {
+            return list::join(elements.iter().map(|e| boolean(e)), ", ");
+        }
+    }
+    boolean(value).to_owned()
+}
+
+/// 64-bit FNV-1a hasher, its result doesn't depend on platform or process
+pub struct Fnv1a(u64);
+
+impl Fnv1a {
+    pub fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Write length of `value` before its bytes, so adjacent strings can't be confused
+    pub fn write_str(&mut self, value: &str) {
+        self.write_len(value.len());
+        self.write(value.as_bytes());
+    }
+
+    pub fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn natural_order() {
+        let mut names = vec!["a10", "a2", "a02", "b", "a", "a2b"];
+        names.sort_by(|a, b| natural(a, b));
+        assert_eq!(names, ["a", "a2", "a2b", "a02", "a10", "b"]);
+    }
+
+    #[test]
+    fn normalized_values() {
+        assert_eq!(normalize("1,2 ,  3", Some(ArrayKind::List)), "1, 2, 3");
+        assert_eq!(normalize("Yes", None), "true");
+        assert_eq!(normalize("on, OFF, \"a,b\"", Some(ArrayKind::List)), "true, false, \"a,b\"");
+        assert_eq!(normalize("a:on,b : 2", Some(ArrayKind::Map)), "a:true, b:2");
+        assert_eq!(normalize("plain text", None), "plain text");
+        // any value with unquoted comma is a list
+        assert_eq!(normalize("1,2 ,  3", None), "1, 2, 3");
+        assert_eq!(normalize("a,b", None), "a, b");
+        assert_eq!(normalize("yes,no", None), "true, false");
+        assert_eq!(normalize("\"a,b\"", None), "\"a,b\"");
+    }
+
+    #[test]
+    fn fnv1a() {
+        let mut hasher = Fnv1a::new();
+        assert_eq!(hasher.finish(), 0xcbf29ce484222325);
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+    }
+}
@@ -0,0 +1,94 @@
+//! Case folding module
+//!
+//! Contains [Aliases] index which is used by case-insensitive [Ini](crate::Ini) and [Section](crate::Section)
+//! to find the spelling of section or key name which was seen first
+use crate::ordered_hashmap::OrderedHashMap;
+use std::collections::HashMap;
+
+/// Index of lowercased names to their stored spelling, [None] means case-sensitive mode
+///
+/// Entries may become stale when names are removed from the map, so every spelling is checked
+/// against the map before use.
+#[derive(Debug, Clone, Default)]
+pub struct Aliases(Option<HashMap<String, String>>);
+
+impl Aliases {
+    /// Create index, `insensitive` enables case folding
+    pub fn new(insensitive: bool) -> Self {
+        Aliases(if insensitive { Some(HashMap::new()) } else { None })
+    }
+
+    pub fn is_insensitive(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Spelling of `name` stored in `map` or `name` itself if there is no such name
+    ///
+    /// Exact spelling and names without uppercase letters are found without allocation.
+    pub fn resolve<'a, V>(&'a self, map: &OrderedHashMap<String, V>, name: &'a str) -> &'a str {
+        let aliases = match &self.0 {
+            Some(aliases) if !map.contains_key(name) => aliases,
+            _ => return name,
+        };
+        let spelling = match name.bytes().any(|b| b.is_ascii_uppercase() || !b.is_ascii()) {
+            true => aliases.get(&name.to_lowercase()),
+            false => aliases.get(name),
+        };
+        match spelling {
+            Some(spelling) if map.contains_key(spelling) => spelling,
+            _ => name,
+        }
+    }
+
+    /// Spelling of `name` which must be used to insert it into `map`: stored spelling if there is one,
+    /// otherwise `name` is registered as the spelling of new element
+    pub fn insert<V>(&mut self, map: &OrderedHashMap<String, V>, name: String) -> String {
+        if self.0.is_none() {
+            return name;
+        }
+        let resolved = self.resolve(map, &name);
+        if resolved != name {
+            return resolved.to_owned();
+        }
+        if let Some(aliases) = &mut self.0 {
+            aliases.insert(name.to_lowercase(), name.clone());
+        }
+        name
+    }
+
+    /// Rename element `old` of `map` to `new` keeping its position, returns stored spelling of `old`
+    /// or [None] if there is no such element or `new` is used by another element
+    pub fn rename<V>(&mut self, map: &mut OrderedHashMap<String, V>, old: &str, new: &str) -> Option<String> {
+        let old = self.resolve(map, old).to_owned();
+        // in case-insensitive mode `new` may be another spelling of existing element
+        let existing = self.resolve(map, new);
+        if existing != new && existing != old {
+            return None;
+        }
+        if !map.rename(old.as_str(), new.to_owned()) {
+            return None;
+        }
+        self.insert(map, new.to_owned());
+        Some(old)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn first_spelling() {
+        let mut map = OrderedHashMap::new();
+        let mut aliases = Aliases::new(true);
+        let name = aliases.insert(&map, "Server".to_owned());
+        map.insert(name, 1);
+        assert_eq!(aliases.insert(&map, "SERVER".to_owned()), "Server");
+        assert_eq!(aliases.resolve(&map, "server"), "Server");
+        // stale spelling is replaced by the next one
+        map.remove("Server");
+        assert_eq!(aliases.resolve(&map, "server"), "server");
+        assert_eq!(aliases.insert(&map, "SERVER".to_owned()), "SERVER");
+        assert_eq!(Aliases::new(false).resolve(&map, "server"), "server");
+    }
+}
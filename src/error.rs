@@ -24,8 +24,54 @@ pub enum ParseError {
     EmptyKey(usize),
 }
 
+/// Errors returned by fallible getters such as [Ini::try_get](crate::Ini::try_get)
+/// and [Ini::require](crate::Ini::require)
+#[derive(Debug, PartialEq)]
+pub enum GetError {
+    /// Section with given name doesn't exist
+    MissingSection(String),
+    /// Key doesn't exist in the section
+    MissingKey {
+        /// section name
+        section: String,
+        /// key name
+        key: String,
+    },
+    /// Key doesn't exist in [Section](crate::Section) which was queried directly,
+    /// so name of the section is unknown
+    MissingSectionKey(String),
+    /// Value exists but cannot be converted to the requested type
+    Conversion {
+        /// raw value as stored in the document
+        value: String,
+        /// name of the requested type
+        type_name: &'static str,
+        /// message of the underlying parse error
+        message: String,
+    },
+}
+
+/// Error returned by built-in [FromIniValue](crate::FromIniValue) implementations
+/// which have no suitable error type in standard library
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueError {
+    message: String,
+}
+
+impl ValueError {
+    /// Create an error with given message
+    pub fn new<S>(message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        ValueError { message: message.into() }
+    }
+}
+
 impl error::Error for Error {}
 impl error::Error for ParseError {}
+impl error::Error for GetError {}
+impl error::Error for ValueError {}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -46,6 +92,25 @@ impl fmt::Display for ParseError {
     }
 }
 
+impl fmt::Display for GetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GetError::MissingSection(section) => write!(f, "Section `{}` not found", section),
+            GetError::MissingSectionKey(key) => write!(f, "Key `{}` not found in section", key),
+            GetError::MissingKey { section, key } => write!(f, "Key `{}` not found in section `{}`", key, section),
+            GetError::Conversion { value, type_name, message } => {
+                write!(f, "Cannot convert `{}` to {}: {}", value, type_name, message)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
 impl From<ParseError> for Error {
     fn from(error: ParseError) -> Self {
         Error::Parse(error)
@@ -0,0 +1,314 @@
+//! Lazy document module
+//!
+//! Contains [LazyIni] which indexes byte offsets of sections and parses every section
+//! only when it is accessed for the first time
+use crate::case::Aliases;
+use crate::error::Error;
+use crate::ordered_hashmap::OrderedHashMap;
+use crate::parser::{parse_line, Builder, ParseOptions, Parsed};
+use crate::{FromIniValue, Section, SectionIter};
+use std::cell::{OnceCell, RefCell};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::mem;
+use std::path::Path;
+use std::str;
+
+/// Read-only ini document which parses sections on demand
+///
+/// Opening reads the file once to find where sections start, every section is parsed
+/// the first time [`get()`](LazyIni::get) or [`section_iter()`](LazyIni::section_iter) touches it
+/// and kept for later calls. Syntax errors are reported only for sections which contain them,
+/// so a broken section doesn't prevent reading the others. Keys before the first section
+/// belong to the section with empty name. Comments above section headers are not kept.
+///
+/// # Example
+/// ```
+/// # use tini::LazyIni;
+/// use std::io::Cursor;
+///
+/// let text = "[host1]\nip = 10.0.0.1\n[host2]\nbroken line\n[host3]\nip = 10.0.0.3";
+/// let conf = LazyIni::new(Cursor::new(text)).unwrap();
+///
+/// assert_eq!(conf.get("host3", "ip"), Some("10.0.0.3".to_owned()));
+/// assert!(conf.try_section("host2").is_err());
+/// assert_eq!(conf.sections().collect::<Vec<_>>(), ["host1", "host2", "host3"]);
+/// ```
+pub struct LazyIni<R> {
+    source: RefCell<R>,
+    options: ParseOptions,
+    index: OrderedHashMap<String, LazySection>,
+    aliases: Aliases,
+    empty_section: Section,
+}
+
+/// Part of the file between section header and the next header
+struct Span {
+    start: u64,
+    end: u64,
+    /// number of the first line
+    line: usize,
+    /// header spelling, the section with empty name has no header
+    header: Option<String>,
+}
+
+/// Index entry of section which may occur in the file several times
+#[derive(Default)]
+struct LazySection {
+    spans: Vec<Span>,
+    parsed: OnceCell<Result<Option<Section>, Error>>,
+}
+
+impl LazyIni<File> {
+    /// Open file at `path` and index its sections
+    ///
+    /// # Errors
+    /// This function will return an [Error] if file cannot be opened or read
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use tini::LazyIni;
+    /// let inventory = LazyIni::open("inventory.ini").unwrap();
+    ///
+    /// let address: Option<String> = inventory.get("host42", "address");
+    /// ```
+    pub fn open<S>(path: &S) -> Result<Self, Error>
+    where
+        S: AsRef<Path> + ?Sized,
+    {
+        LazyIni::open_with(path, &ParseOptions::default())
+    }
+
+    /// Open file at `path` and index its sections, sections are parsed using parse `options`
+    ///
+    /// # Errors
+    /// This function will return an [Error] if file cannot be opened or read
+    pub fn open_with<S>(path: &S, options: &ParseOptions) -> Result<Self, Error>
+    where
+        S: AsRef<Path> + ?Sized,
+    {
+        LazyIni::new_with(File::open(path)?, options)
+    }
+}
+
+impl<R: Read + Seek> LazyIni<R> {
+    /// Index sections of `source` from its beginning
+    ///
+    /// # Errors
+    /// This function will return an [Error] if `source` cannot be read
+    pub fn new(source: R) -> Result<Self, Error> {
+        LazyIni::new_with(source, &ParseOptions::default())
+    }
+
+    /// Index sections of `source` from its beginning, sections are parsed using parse `options`
+    ///
+    /// # Errors
+    /// This function will return an [Error] if `source` cannot be read
+    pub fn new_with(mut source: R, options: &ParseOptions) -> Result<Self, Error> {
+        source.seek(SeekFrom::Start(0))?;
+        let mut aliases = Aliases::new(options.case_insensitive);
+        let index = Self::build_index(&mut source, &mut aliases)?;
+        Ok(LazyIni {
+            source: RefCell::new(source),
+            options: options.clone(),
+            index,
+            aliases,
+            empty_section: Section::new(),
+        })
+    }
+
+    /// Find section headers, only lines starting with `[` are parsed
+    fn build_index(source: &mut R, aliases: &mut Aliases) -> Result<OrderedHashMap<String, LazySection>, Error> {
+        let mut index = OrderedHashMap::new();
+        let mut push = |span: Span| {
+            let name = aliases.insert(&index, span.header.clone().unwrap_or_default());
+            index.entry(name).or_insert_with(LazySection::default).spans.push(span);
+        };
+        let mut reader = BufReader::new(source);
+        let mut buffer = Vec::new();
+        let mut current = Span { start: 0, end: 0, line: 1, header: None };
+        // lines before the first header form a section only if there are keys among them
+        let mut content = false;
+        let mut offset = 0;
+        let mut line = 0;
+        loop {
+            buffer.clear();
+            let read = reader.read_until(b'\n', &mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            line += 1;
+            offset += read as u64;
+            // invalid line belongs to the current section and is reported when it is parsed
+            let text = match str::from_utf8(&buffer) {
+                Ok(text) => text,
+                Err(_) => {
+                    content = true;
+                    continue;
+                }
+            };
+            // the same whitespace as in `parse_line`
+            match text.trim_start().as_bytes().first() {
+                Some(b'[') => (),
+                Some(b';' | b'#') | None => continue,
+                Some(_) => {
+                    content = true;
+                    continue;
+                }
+            }
+            // broken header belongs to the current section and is reported when it is parsed
+            let name = match parse_line(text, line) {
+                Ok(Parsed::Section(name)) => name.to_owned(),
+                _ => continue,
+            };
+            current.end = offset - read as u64;
+            let next = Span { start: offset, end: 0, line: line + 1, header: Some(name) };
+            let span = mem::replace(&mut current, next);
+            if span.header.is_some() || content {
+                push(span);
+            }
+        }
+        current.end = offset;
+        if current.header.is_some() || content {
+            push(current);
+        }
+        Ok(index)
+    }
+
+    /// Parse all parts of `section` which is stored under `name`
+    fn parse(&self, name: &str, section: &LazySection) -> Result<Option<Section>, Error> {
+        let mut source = self.source.borrow_mut();
+        let mut builder = Builder::new(&self.options);
+        let mut buffer = Vec::new();
+        for span in &section.spans {
+            if let Some(header) = &span.header {
+                builder.push(Parsed::Section(header), header);
+            }
+            buffer.resize((span.end - span.start) as usize, 0);
+            source.seek(SeekFrom::Start(span.start))?;
+            source.read_exact(&mut buffer)?;
+            let text = str::from_utf8(&buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            for (index, line) in text.lines().enumerate() {
+                builder.push(parse_line(line, span.line + index)?, line);
+            }
+        }
+        Ok(builder.finish().remove_section(name))
+    }
+
+    /// Parsed `section`, it's parsed on the first call
+    ///
+    /// Returns `Ok(None)` if there is no such section or it has no keys.
+    ///
+    /// # Errors
+    /// Returns the [Error] of parsing or reading `section`, the same error is returned on every call
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Error, LazyIni, ParseError};
+    /// use std::io::Cursor;
+    ///
+    /// let conf = LazyIni::new(Cursor::new("[a]\nx = 1\n[b]\n= 2")).unwrap();
+    ///
+    /// assert_eq!(conf.try_section("a").unwrap().map(|s| s.len()), Some(1));
+    /// assert!(matches!(conf.try_section("b"), Err(Error::Parse(ParseError::EmptyKey(4)))));
+    /// assert!(conf.try_section("c").unwrap().is_none());
+    /// ```
+    pub fn try_section(&self, section: &str) -> Result<Option<&Section>, &Error> {
+        let name = self.aliases.resolve(&self.index, section);
+        match self.index.get(name) {
+            Some(lazy) => lazy.parsed.get_or_init(|| self.parse(name, lazy)).as_ref().map(Option::as_ref),
+            None => Ok(None),
+        }
+    }
+
+    /// Parsed `section` or [None] if it doesn't exist or cannot be parsed
+    pub fn get_section(&self, section: &str) -> Option<&Section> {
+        self.try_section(section).ok().flatten()
+    }
+
+    /// Get scalar value of `key` in `section`, see [Ini::get](crate::Ini::get)
+    ///
+    /// Returns [None] if `section` cannot be parsed.
+    pub fn get<T>(&self, section: &str, key: &str) -> Option<T>
+    where
+        T: FromIniValue,
+    {
+        self.get_section(section).and_then(|s| s.get(key))
+    }
+
+    /// Get vector value of `key` in `section`, see [Ini::get_vec](crate::Ini::get_vec)
+    ///
+    /// Returns [None] if `section` cannot be parsed.
+    pub fn get_vec<T>(&self, section: &str, key: &str) -> Option<Vec<T>>
+    where
+        T: FromIniValue,
+    {
+        self.get_section(section).and_then(|s| s.get_vec(key))
+    }
+
+    /// Iterate over key-value pairs of `section` in order of appearance,
+    /// iterator is empty if there is no such section or it cannot be parsed
+    pub fn section_iter(&self, section: &str) -> SectionIter<'_> {
+        self.get_section(section).unwrap_or(&self.empty_section).iter()
+    }
+
+    /// Names of indexed sections in order of appearance, sections are not parsed
+    pub fn sections(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    /// Returns `true` if the file has header of `section`
+    pub fn contains_section(&self, section: &str) -> bool {
+        self.index.contains_key(self.aliases.resolve(&self.index, section))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::Ini;
+    use std::io::Cursor;
+
+    #[test]
+    fn parse_on_access() -> Result<(), Error> {
+        let text = "; about\nglobal = 1\n\n[a]\nx = 1\n[b]\ny = broken\nbroken\n[A]\nz = 3 ; inline\r\n";
+        let conf = LazyIni::new(Cursor::new(text))?;
+        assert_eq!(conf.sections().collect::<Vec<_>>(), ["", "a", "b", "A"]);
+        assert!(conf.index.iter().all(|(_, section)| section.parsed.get().is_none()));
+
+        assert_eq!(conf.get::<u8>("A", "z"), Some(3));
+        assert_eq!(conf.section_iter("a").count(), 1);
+        assert!(conf.index.get("b").is_some_and(|section| section.parsed.get().is_none()));
+        assert!(matches!(conf.try_section("b"), Err(Error::Parse(ParseError::IncorrectSyntax(8)))));
+        assert_eq!(conf.get::<String>("b", "y"), None);
+        assert_eq!(conf.get::<u8>("", "global"), Some(1));
+        assert!(!conf.contains_section("c"));
+        Ok(())
+    }
+
+    #[test]
+    fn same_as_owned() -> Result<(), Error> {
+        let text = "[a]\nx = 1\nlist[] = 1\n[b]\ny = 2\n[A]\nx = 3\nlist[] = 2\n";
+        let options = ParseOptions::new().case_insensitive(true).php_arrays(true);
+        let conf = LazyIni::new_with(Cursor::new(text), &options)?;
+        let owned = Ini::from_string_with(text, &options)?;
+        assert_eq!(conf.sections().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(conf.get_section("A"), owned.get_section("a"));
+        assert_eq!(conf.get_vec::<u8>("a", "list"), Some(vec![1, 2]));
+        Ok(())
+    }
+
+    #[test]
+    fn unicode_indent() -> Result<(), Error> {
+        let text = "[a]\nx = 1\n\u{a0}[b]\ny = 2\n\u{2003}; comment\n[c]\nz = 3\n";
+        let conf = LazyIni::new(Cursor::new(text))?;
+        let owned = Ini::from_string(text)?;
+        assert_eq!(conf.sections().collect::<Vec<_>>(), ["a", "b", "c"]);
+        for name in ["a", "b", "c"] {
+            assert_eq!(conf.get_section(name), owned.get_section(name));
+        }
+        assert_eq!(conf.get::<u8>("b", "y"), Some(2));
+        Ok(())
+    }
+}
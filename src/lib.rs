@@ -5,11 +5,25 @@
 //! Features:
 //!
 //! * no dependencies;
-//! * parsing [from file](Ini::from_file), [from reader](Ini::from_reader) and [from string](Ini::from_string);
-//! * [convert parsed value to given type](Ini::get);
-//! * [parse comma-separated lists to vectors](Ini::get_vec);
-//! * construct new ini-structure with [method chaining](Ini::item);
-//! * writing [to file](Ini::to_file), [to writer](Ini::to_writer) and [to string](Ini#impl-Display).
+//! * parsing [from file](Ini::from_file), [from reader](Ini::from_reader) and [from string](Ini::from_string),
+//!   also [event by event](IniReader) without loading the whole file
+//!   into [borrowed document](IniRef) without copying the text
+//!   and [lazily](LazyIni) parsing only the sections which are accessed;
+//! * optional [php-style arrays](ParseOptions::php_arrays) `name[] = value`;
+//! * [locale-suffixed keys](Ini::get_localized) `Name[de_DE] = value`;
+//! * optional [case-insensitive](Ini::new_case_insensitive) section and key names;
+//! * [convert parsed value to given type](Ini::get) with [FromIniValue] and back with [ToIniValue],
+//!   types with only [FromStr](std::str::FromStr) and [Display](fmt::Display) are supported
+//!   with [impl_ini_value_via_from_str] and [impl_ini_value_via_display];
+//! * [parse comma-separated lists to vectors](Ini::get_vec), [range lists](Ini::get_ranges) and [inline maps to map collections](Ini::get_map);
+//! * integers in [hexadecimal](Hex), [octal](Octal) and [binary](Binary) form;
+//! * human-friendly [durations](std::time::Duration), [byte sizes](ByteSize) and [percentages](Percent);
+//! * construct new ini-structure with [method chaining](Ini::item) or [edit it in place](Ini::set),
+//!   also with [entry API](Ini::entry);
+//! * [read](Ini::get_key_comment) and [write](Ini::comment_key) comments above sections and keys;
+//! * [canonical form](Ini::canonicalize) and stable [content hash](Ini::content_hash);
+//! * writing [to file](Ini::to_file), [to writer](Ini::to_writer) and [to string](Ini#impl-Display),
+//!   also with [custom format](WriteOptions).
 //!
 //! # Examples
 //! ## Read from buffer and get string values
@@ -39,46 +53,168 @@
 //! assert_eq!(consts, [3.1416, 2.7183]);
 //! assert_eq!(lost, [4, 8, 15, 16, 23, 42]);
 //! ````
+//!
+//! # Migration from 1.x
+//! Getters and setters are bound by [FromIniValue] and [ToIniValue] instead of
+//! [FromStr](std::str::FromStr) and [Display](fmt::Display). There is no blanket implementation
+//! for [FromStr](std::str::FromStr) types, because integers with radix prefixes, flexible booleans,
+//! [Option] and [Vec] need their own implementations. Own types which have only
+//! [FromStr](std::str::FromStr) and [Display](fmt::Display) need one line each:
+//! ````
+//! # use tini::{impl_ini_value_via_display, impl_ini_value_via_from_str, Ini};
+//! # use std::net::AddrParseError;
+//! # use std::{fmt, str::FromStr};
+//! struct Host(std::net::IpAddr);
+//! # impl FromStr for Host {
+//! #     type Err = AddrParseError;
+//! #     fn from_str(s: &str) -> Result<Self, Self::Err> { s.parse().map(Host) }
+//! # }
+//! # impl fmt::Display for Host {
+//! #     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { self.0.fmt(f) }
+//! # }
+//!
+//! impl_ini_value_via_from_str!(Host);
+//! impl_ini_value_via_display!(Host);
+//!
+//! let conf = Ini::new().section("db").item("host", Host([10, 0, 0, 1].into()));
+//! let host: Option<Host> = conf.get("db", "host");
+//! assert_eq!(host.map(|h| h.0.to_string()), Some("10.0.0.1".to_owned()));
+//! ````
+//!
+//! Values which 1.x failed to read may now be read: `yes`/`on`/`1` are booleans, integers
+//! accept `0x`, `0o`, `0b` prefixes and `_` separators, empty list value is an empty vector.
+mod borrowed;
+mod canonical;
+mod case;
 mod error;
+mod lazy;
+mod list;
+mod locale;
 mod ordered_hashmap;
 mod parser;
+mod radix;
+mod range;
+mod reader;
+mod units;
+mod value;
+mod writer;
 
-pub use error::{Error, ParseError};
-use ordered_hashmap::OrderedHashMap;
-use parser::{parse_line, Parsed};
+pub use borrowed::{IniRef, IniRefIter, SectionRef, SectionRefIter};
+pub use canonical::SortOrder;
+use case::Aliases;
+pub use error::{Error, GetError, ParseError, ValueError};
+pub use lazy::LazyIni;
+use ordered_hashmap::{self as map, OrderedHashMap};
+pub use parser::ParseOptions;
+use parser::{parse_line, split_array_key, ArrayKind, Builder};
+pub use radix::{Binary, Hex, Octal};
+pub use range::RangeInteger;
+pub use reader::{Event, IniReader};
+use std::any::type_name;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::fs::File;
-use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::iter::Iterator;
+use std::ops::{Index, IndexMut};
 use std::path::Path;
-use std::str::FromStr;
+pub use units::{ByteSize, Percent};
+use value::{join_list, parse_list};
+pub use value::{FromIniValue, ToIniValue};
+pub use writer::WriteOptions;
 
 /// Structure for INI-file data
-#[derive(Debug)]
+///
+/// Two documents are equal if they have equal sections in the same order,
+/// use [`eq_unordered()`](Ini::eq_unordered) to ignore the order. Comments are not compared.
+#[derive(Debug, Clone)]
 pub struct Ini {
     #[doc(hidden)]
     document: OrderedHashMap<String, Section>,
     last_section_name: String,
     empty_section: Section,
+    /// spelling of sections in case-insensitive mode
+    aliases: Aliases,
+    /// comment at the beginning of file
+    header: Option<String>,
+    /// comment after the last key of file
+    footer: Option<String>,
+    comment_prefix: String,
 }
 
 impl Ini {
     /// Create an empty Ini (similar to [Ini::default])
     pub fn new() -> Ini {
-        Ini { document: OrderedHashMap::new(), last_section_name: String::new(), empty_section: Section::new() }
+        Ini::with_aliases(Aliases::default())
+    }
+
+    /// Create an empty Ini with case-insensitive section and key names
+    ///
+    /// Names which differ only by case refer to the same section or key, the spelling seen first
+    /// is kept for output. Use [ParseOptions::case_insensitive] to parse documents in this mode.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::new_case_insensitive().section("Server")
+    ///                                       .item("Port", 80)
+    ///                                       .section("SERVER")
+    ///                                       .item("port", 8080);
+    ///
+    /// assert_eq!(conf.get("server", "PORT"), Some(8080));
+    /// assert_eq!(conf.to_string(), "[Server]\nPort = 8080\n");
+    /// ```
+    pub fn new_case_insensitive() -> Ini {
+        Ini::with_aliases(Aliases::new(true))
+    }
+
+    fn with_aliases(aliases: Aliases) -> Ini {
+        Ini {
+            document: OrderedHashMap::new(),
+            last_section_name: String::new(),
+            empty_section: Section::new(),
+            aliases,
+            header: None,
+            footer: None,
+            comment_prefix: ";".to_owned(),
+        }
+    }
+
+    /// Returns `true` if section and key names are case-insensitive, see [Ini::new_case_insensitive]
+    pub fn is_case_insensitive(&self) -> bool {
+        self.aliases.is_insensitive()
+    }
+
+    /// Private method which returns stored spelling of `section`
+    fn name<'a>(&'a self, section: &'a str) -> &'a str {
+        self.aliases.resolve(&self.document, section)
+    }
+
+    /// Private method which returns `section`, creating it if necessary
+    fn section_or_insert(&mut self, section: String) -> &mut Section {
+        let name = self.aliases.insert(&self.document, section);
+        let aliases = Aliases::new(self.is_case_insensitive());
+        self.document.entry(name).or_insert_with(|| Section::with_aliases(aliases))
     }
 
     /// Private construct method which create [Ini] struct from input string
-    fn parse(string: &str) -> Result<Ini, Error> {
-        let mut result = Ini::new();
-        for (index, line) in string.lines().enumerate() {
-            match parse_line(&line, index + 1)? {
-                Parsed::Section(name) => result = result.section(name),
-                Parsed::Value(name, value) => result = result.item(name, value),
-                _ => (),
-            };
+    fn parse(text: &str, options: &ParseOptions) -> Result<Ini, Error> {
+        let mut builder = Builder::new(options);
+        for (index, line) in text.lines().enumerate() {
+            builder.push(parse_line(line, index + 1)?, line);
+        }
+        Ok(builder.finish())
+    }
+
+    /// Private construct method which create [Ini] struct from lines of `reader`
+    fn parse_reader<R: BufRead>(mut reader: IniReader<R>, options: &ParseOptions) -> Result<Ini, Error> {
+        let mut builder = Builder::new(options);
+        while let Some(line) = reader.read_parsed() {
+            let (parsed, line) = line?;
+            builder.push(parsed, line);
         }
-        Ok(result)
+        Ok(builder.finish())
     }
 
     /// Construct Ini from file
@@ -108,12 +244,30 @@ impl Ini {
     /// assert!(conf.ok().is_some());
     /// ```
     pub fn from_file<S>(path: &S) -> Result<Ini, Error>
+    where
+        S: AsRef<Path> + ?Sized,
+    {
+        Ini::from_file_with(path, &ParseOptions::default())
+    }
+
+    /// Construct Ini from file using parse `options`
+    ///
+    /// # Errors
+    /// This function will return an [Error] if file cannot be opened or parsed
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use tini::{Ini, ParseOptions};
+    /// let conf = Ini::from_file_with("php.ini", &ParseOptions::new().php_arrays(true));
+    ///
+    /// assert!(conf.ok().is_some());
+    /// ```
+    pub fn from_file_with<S>(path: &S, options: &ParseOptions) -> Result<Ini, Error>
     where
         S: AsRef<Path> + ?Sized,
     {
         let file = File::open(path)?;
-        let mut reader = BufReader::new(file);
-        Ini::from_reader(&mut reader)
+        Ini::parse_reader(IniReader::new(BufReader::new(file)), options)
     }
 
     /// Construct Ini from any struct who implement [Read](std::io::Read) trait
@@ -137,9 +291,28 @@ impl Ini {
     where
         R: Read,
     {
-        let mut buffer = String::new();
-        reader.read_to_string(&mut buffer)?;
-        Ini::parse(&buffer)
+        Ini::from_reader_with(reader, &ParseOptions::default())
+    }
+
+    /// Construct Ini from any struct who implement [Read](std::io::Read) trait using parse `options`
+    ///
+    /// # Errors
+    /// This function will return an [Error] if reader cannot be read or parsed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, ParseOptions};
+    /// let mut reader = "[section]\nitem[] = value".as_bytes();
+    ///
+    /// let conf = Ini::from_reader_with(&mut reader, &ParseOptions::new().php_arrays(true)).unwrap();
+    ///
+    /// assert_eq!(conf.get_vec::<String>("section", "item").unwrap(), ["value"]);
+    /// ```
+    pub fn from_reader_with<R>(reader: &mut R, options: &ParseOptions) -> Result<Ini, Error>
+    where
+        R: Read,
+    {
+        Ini::parse_reader(IniReader::new(BufReader::new(reader)), options)
     }
 
     /// Construct Ini from any type of string which can be [Into]ed to String
@@ -159,7 +332,28 @@ impl Ini {
     where
         S: Into<String>,
     {
-        Ini::parse(&buf.into())
+        Ini::from_string_with(buf, &ParseOptions::default())
+    }
+
+    /// Construct Ini from any type of string which can be [Into]ed to String using parse `options`
+    ///
+    /// # Errors
+    /// This function will return an [Error] if buffer cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, ParseOptions};
+    /// let options = ParseOptions::new().php_arrays(true);
+    /// let conf = Ini::from_string_with("[section]\nlist[] = 1\nlist[] = 2", &options).unwrap();
+    ///
+    /// let value: Option<Vec<u8>> = conf.get_vec("section", "list");
+    /// assert_eq!(value, Some(vec![1, 2]));
+    /// ```
+    pub fn from_string_with<S>(buf: S, options: &ParseOptions) -> Result<Ini, Error>
+    where
+        S: Into<String>,
+    {
+        Ini::parse(&buf.into(), options)
     }
 
     /// Write Ini to file. This function is similar to [from_file](Ini::from_file) in use.
@@ -170,9 +364,26 @@ impl Ini {
     where
         S: AsRef<Path> + ?Sized,
     {
-        let file = File::create(path)?;
-        let mut writer = BufWriter::new(file);
-        self.to_writer(&mut writer)
+        self.to_file_with(path, &WriteOptions::default())
+    }
+
+    /// Write Ini to file using write `options`
+    ///
+    /// # Errors
+    /// Errors returned by [File::create] and [Write::write_all]
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use tini::{Ini, WriteOptions};
+    /// let conf = Ini::new().section("a").item("x", 1);
+    ///
+    /// conf.to_file_with("windows.ini", &WriteOptions::new().line_ending("\r\n")).unwrap();
+    /// ```
+    pub fn to_file_with<S>(&self, path: &S, options: &WriteOptions) -> Result<(), io::Error>
+    where
+        S: AsRef<Path> + ?Sized,
+    {
+        writer::write_buffered(self, File::create(path)?, options)
     }
 
     /// Write [Ini] to any struct who implement [Write] trait.
@@ -198,8 +409,48 @@ impl Ini {
     where
         W: Write,
     {
-        writer.write_all(self.to_string().as_bytes())?;
-        Ok(())
+        self.to_writer_with(writer, &WriteOptions::default())
+    }
+
+    /// Write [Ini] to any struct who implement [Write] trait using write `options`
+    ///
+    /// Output is buffered and written section by section, so the whole text is never kept in memory.
+    ///
+    /// # Errors
+    /// Errors returned by [Write::write_all](Write::write_all), writing stops at the first error
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, WriteOptions};
+    /// let conf = Ini::new().section("a").item("a", 1);
+    ///
+    /// let mut output = Vec::new();
+    /// conf.to_writer_with(&mut output, &WriteOptions::new().delimiter("=")).unwrap();
+    ///
+    /// assert_eq!(output, b"[a]\na=1\n");
+    /// ```
+    pub fn to_writer_with<W>(&self, writer: &mut W, options: &WriteOptions) -> Result<(), io::Error>
+    where
+        W: Write,
+    {
+        writer::write_buffered(self, writer, options)
+    }
+
+    /// Format [Ini] to string using write `options`, [Display](Ini#impl-Display) uses default options
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, WriteOptions};
+    /// let conf = Ini::new().section("a").item("x", 1).section("b").item("y", 2);
+    /// let options = WriteOptions::new().delimiter("=").blank_lines(0);
+    ///
+    /// assert_eq!(conf.to_string_with(&options), "[a]\nx=1\n[b]\ny=2\n");
+    /// ```
+    pub fn to_string_with(&self, options: &WriteOptions) -> String {
+        let mut output = String::new();
+        // writing to String never fails
+        let _ = writer::write(self, &mut output, options);
+        output
     }
 
     /// Set section name for the following methods in chain ([`item()`](Ini::item), [`items()`](Ini::items), etc.)
@@ -229,7 +480,7 @@ impl Ini {
     /// or replace value if key already in section
     ///
     /// - `name` must support [Into] to [String]
-    /// - `value` must support [Display](fmt::Display) to support conversion to [String]
+    /// - `value` must implement [ToIniValue] trait for conversion to [String]
     ///
     /// # Example
     /// ```
@@ -246,22 +497,21 @@ impl Ini {
     pub fn item<N, V>(mut self, name: N, value: V) -> Self
     where
         N: Into<String>,
-        V: fmt::Display,
+        V: ToIniValue,
     {
-        self.document
-            .entry(self.last_section_name.clone())
-            .or_insert_with(Section::new)
-            .inner
-            .insert(name.into(), value.to_string());
+        self.current_section().insert(name.into(), value.to_ini_value());
         self
     }
 
     /// Like [`item()`](Ini::item), but for vectors
     ///
     /// - `name` must support [Into] to [String]
-    /// - `vector` elements must support [Display](fmt::Display) to support conversion to [String]
+    /// - `vector` elements must implement [ToIniValue] trait for conversion to [String]
     /// - `sep` arbitrary string delimiter
     ///
+    /// Elements which contain separator, quotes, backslashes or surrounding whitespace are written in quotes,
+    /// so [`get_vec_with_sep()`](Ini::get_vec_with_sep) always reads the same vector back.
+    ///
     /// # Example
     /// ```
     /// # use tini::Ini;
@@ -277,24 +527,25 @@ impl Ini {
     ///
     /// assert_eq!(va, Some(vec![1, 2, 3, 4]));
     /// assert_eq!(vb, ["a", "b", "c"]);
+    ///
+    /// // elements with separators are quoted
+    /// let conf = Ini::new().section("default").item_vec_with_sep("c", &["x, y", "z"], ", ");
+    /// assert_eq!(conf.to_string(), "[default]\nc = \"x, y\", z\n");
+    /// assert_eq!(conf.get_vec::<String>("default", "c").unwrap(), ["x, y", "z"]);
     /// ```
     pub fn item_vec_with_sep<S, V>(mut self, name: S, vector: &[V], sep: &str) -> Self
     where
         S: Into<String>,
-        V: fmt::Display,
+        V: ToIniValue,
     {
-        let vector_data = vector.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(sep);
-        self.document
-            .entry(self.last_section_name.clone())
-            .or_insert_with(Section::new)
-            .insert(name.into(), vector_data);
+        self.current_section().insert(name.into(), join_list(vector, sep));
         self
     }
 
     /// Equivalent of [`item_vec_with_sep(name, vector, ", ")`](Ini::item_vec_with_sep)
     ///
     /// - `name` must support [Into] to [String]
-    /// - `vector` elements must support [Display](fmt::Display) to support conversion to [String]
+    /// - `vector` elements must implement [ToIniValue] trait for conversion to [String]
     ///
     /// # Example
     /// ```
@@ -315,11 +566,115 @@ impl Ini {
     pub fn item_vec<S, V>(self, name: S, vector: &[V]) -> Self
     where
         S: Into<String>,
-        V: fmt::Display,
+        V: ToIniValue,
     {
         self.item_vec_with_sep(name, vector, ", ")
     }
 
+    /// Like [`item()`](Ini::item), but sets value of `name` translated to `locale`, i.e. value of `name[locale]`
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::new().section("Desktop Entry")
+    ///                      .item("Name", "Editor")
+    ///                      .item_localized("Name", "de", "Bearbeiter");
+    ///
+    /// assert_eq!(conf.to_string(), "[Desktop Entry]\nName = Editor\nName[de] = Bearbeiter\n");
+    /// ```
+    pub fn item_localized<V>(mut self, name: &str, locale: &str, value: V) -> Self
+    where
+        V: ToIniValue,
+    {
+        self.current_section().set_localized(name, locale, value);
+        self
+    }
+
+    /// Like [`item_vec()`](Ini::item_vec), but compresses integers into range list, e.g. `0-3, 8, 10-11`
+    ///
+    /// Values are sorted and deduplicated before writing.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::new().section("cpu").item_ranges("pinned", &[0, 1, 2, 3, 8, 10, 11]);
+    ///
+    /// assert_eq!(conf.to_string(), "[cpu]\npinned = 0-3, 8, 10-11\n");
+    /// ```
+    pub fn item_ranges<S, V>(self, name: S, values: &[V]) -> Self
+    where
+        S: Into<String>,
+        V: RangeInteger,
+    {
+        self.item_ranges_with_sep(name, values, ", ")
+    }
+
+    /// Like [`item_ranges()`](Ini::item_ranges), but with `sep` between ranges
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::new().section("net").item_ranges_with_sep("vlans", &[10u16, 11, 12, 20], " ");
+    ///
+    /// assert_eq!(conf.to_string(), "[net]\nvlans = 10-12 20\n");
+    /// ```
+    pub fn item_ranges_with_sep<S, V>(self, name: S, values: &[V], sep: &str) -> Self
+    where
+        S: Into<String>,
+        V: RangeInteger,
+    {
+        self.item_vec_with_sep(name, &range::compress(values), sep)
+    }
+
+    /// Like [`item()`](Ini::item), but for inline maps with `:` between key and value and `, ` between entries
+    ///
+    /// Entries are written in iteration order of `map`.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::new().section("limits").item_map("weights", vec![("cpu", 2), ("io", 1), ("net", 3)]);
+    ///
+    /// assert_eq!(conf.to_string(), "[limits]\nweights = cpu:2, io:1, net:3\n");
+    /// ```
+    pub fn item_map<S, I, K, V>(self, name: S, map: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = (K, V)>,
+        K: ToIniValue,
+        V: ToIniValue,
+    {
+        self.item_map_with_sep(name, map, ":", ", ")
+    }
+
+    /// Like [`item_map()`](Ini::item_map), but with `pair_sep` between key and value
+    /// and `entry_sep` between entries
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let mut map = BTreeMap::new();
+    /// map.insert("b", 2);
+    /// map.insert("a", 1);
+    /// let conf = Ini::new().section("default").item_map_with_sep("map", &map, "=", " | ");
+    ///
+    /// assert_eq!(conf.to_string(), "[default]\nmap = a=1 | b=2\n");
+    /// ```
+    pub fn item_map_with_sep<S, I, K, V>(mut self, name: S, map: I, pair_sep: &str, entry_sep: &str) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = (K, V)>,
+        K: ToIniValue,
+        V: ToIniValue,
+    {
+        let entries = map.into_iter().map(|(k, v)| (k.to_ini_value(), v.to_ini_value()));
+        let map_data = list::join_map(entries, pair_sep, entry_sep);
+        self.current_section().insert(name.into(), map_data);
+        self
+    }
+
     /// Append pairs from any object supporting [IntoIterator] to the section, specified in last [`section()`](Ini::section) call.
     ///
     /// # Example
@@ -351,11 +706,11 @@ impl Ini {
     pub fn items<K, V, I>(mut self, items: I) -> Self
     where
         K: Into<String>,
-        V: fmt::Display,
+        V: ToIniValue,
         I: IntoIterator<Item = (K, V)>,
     {
         for (k, v) in items {
-            self = self.item(k.into(), v.to_string());
+            self = self.item(k, v);
         }
         self
     }
@@ -380,7 +735,7 @@ impl Ini {
     /// assert_eq!(config.to_string(), "[two]\na = 1\n");
     /// ```
     pub fn clear(mut self) -> Self {
-        self.document.remove(&self.last_section_name);
+        self.document.remove(self.aliases.resolve(&self.document, &self.last_section_name));
         self
     }
 
@@ -400,290 +755,2177 @@ impl Ini {
     /// assert_eq!(config.to_string(), "[one]\na = 1\n");
     /// ```
     pub fn erase(mut self, key: &str) -> Self {
-        self.document.get_mut(&self.last_section_name).and_then(|s| s.remove(key));
+        self.document
+            .get_mut(self.aliases.resolve(&self.document, &self.last_section_name))
+            .and_then(|s| s.remove(key));
         self
     }
 
-    /// Private method which get value by `key` from `section`
-    fn get_raw(&self, section: &str, key: &str) -> Option<&String> {
-        self.document.get(section).and_then(|s| s.get_raw(key))
+    /// Set comment at the beginning of file, empty `text` removes it
+    ///
+    /// Every line of `text` is written with comment prefix, see [`comment_prefix()`](Ini::comment_prefix).
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::new().header("Generated file\ndo not edit").section("a").item("x", 1);
+    ///
+    /// assert_eq!(conf.get_header(), Some("Generated file\ndo not edit"));
+    /// assert_eq!(conf.to_string(), "; Generated file\n; do not edit\n\n[a]\nx = 1\n");
+    /// ```
+    pub fn header(mut self, text: &str) -> Self {
+        self.header = Some(text.to_owned()).filter(|text| !text.is_empty());
+        self
     }
 
-    /// Get scalar value of key in section.
+    /// Set comment at the end of file, empty `text` removes it
     ///
-    /// - output type `T` must implement [FromStr] trait for auto conversion
+    /// The comment is separated from the last section by one blank line.
     ///
     /// # Example
     /// ```
     /// # use tini::Ini;
-    /// let conf = Ini::from_string("[section]\none = 1").unwrap();
+    /// let conf = Ini::new().section("a").item("x", 1).footer("end of file");
     ///
-    /// let value: Option<u8> = conf.get("section", "one");
-    ///
-    /// assert_eq!(value, Some(1));
+    /// assert_eq!(conf.get_footer(), Some("end of file"));
+    /// assert_eq!(conf.to_string(), "[a]\nx = 1\n\n; end of file\n");
     /// ```
-    pub fn get<T>(&self, section: &str, key: &str) -> Option<T>
-    where
-        T: FromStr,
-    {
-        self.get_raw(section, key).and_then(|x| x.parse().ok())
+    pub fn footer(mut self, text: &str) -> Self {
+        self.footer = Some(text.to_owned()).filter(|text| !text.is_empty());
+        self
     }
 
-    /// Get vector value of `key` in `section`. Value should use `,` as separator.
+    /// Set comment above `section`, empty `text` removes it
     ///
-    /// The function returns [None](Option::None) if one of the elements can not be parsed.
-    ///
-    /// - output type `T` must implement [FromStr] trait for auto conversion
+    /// Comment is set only if section exists.
     ///
     /// # Example
     /// ```
     /// # use tini::Ini;
-    /// let conf = Ini::from_string("[section]\nlist = 1, 2, 3, 4").unwrap();
-    ///
-    /// let value: Option<Vec<u8>> = conf.get_vec("section", "list");
+    /// let conf = Ini::new().section("server").item("port", 80).comment_section("server", "HTTP server");
     ///
-    /// assert_eq!(value, Some(vec![1, 2, 3, 4]));
+    /// assert_eq!(conf.get_section_comment("server"), Some("HTTP server"));
+    /// assert_eq!(conf.to_string(), "; HTTP server\n[server]\nport = 80\n");
     /// ```
-    pub fn get_vec<T>(&self, section: &str, key: &str) -> Option<Vec<T>>
-    where
-        T: FromStr,
-    {
-        self.get_vec_with_sep(section, key, ",")
+    pub fn comment_section(mut self, section: &str, text: &str) -> Self {
+        if let Some(section) = self.section_mut(section) {
+            section.set_comment(text);
+        }
+        self
     }
 
-    /// Get vector value of `key` in `section` separated by `sep` string.
-    ///
-    /// The function returns [None](Option::None) if one of the elements can not be parsed or not found.
+    /// Set comment above `key` in `section`, empty `text` removes it
     ///
-    /// - output type `T` must implement [FromStr] trait for auto conversion
+    /// Comment is set only if key exists, see [Section::comment_key].
     ///
     /// # Example
     /// ```
     /// # use tini::Ini;
-    /// let conf = Ini::from_string("[section]\nlist = 1|2|3|4").unwrap();
-    ///
-    /// let value: Option<Vec<u8>> = conf.get_vec_with_sep("section", "list", "|");
+    /// let conf = Ini::new().section("server").item("port", 80).comment_key("server", "port", "listening port");
     ///
-    /// assert_eq!(value, Some(vec![1, 2, 3, 4]));
+    /// assert_eq!(conf.get_key_comment("server", "port"), Some("listening port"));
+    /// assert_eq!(conf.to_string(), "[server]\n; listening port\nport = 80\n");
     /// ```
-    pub fn get_vec_with_sep<T>(&self, section: &str, key: &str, sep: &str) -> Option<Vec<T>>
-    where
-        T: FromStr,
-    {
-        self.get_raw(section, key)
-            .and_then(|x| x.split(sep).map(|s| s.trim().parse()).collect::<Result<Vec<T>, _>>().ok())
+    pub fn comment_key(mut self, section: &str, key: &str, text: &str) -> Self {
+        if let Some(section) = self.section_mut(section) {
+            section.comment_key(key, text);
+        }
+        self
     }
 
-    /// An iterator visiting all key-value pairs of a section in order of appearance.
+    /// Set prefix of comment lines written by [Display](Ini#impl-Display), `;` by default
     ///
-    /// If section with given name doesn't exist in document, method returns empty iterator
+    /// Parsed documents keep the prefix of their first comment line.
     ///
     /// # Example
     /// ```
     /// # use tini::Ini;
-    /// let conf = Ini::from_string(["[search]",
-    ///                              "g = google.com",
-    ///                              "dd = duckduckgo.com"].join("\n")).unwrap();
-    ///
-    /// let mut search = conf.section_iter("search");
-    /// assert_eq!(search.next(), Some((&"g".to_string(), &"google.com".to_string())));
-    /// assert_eq!(search.next(), Some((&"dd".to_string(), &"duckduckgo.com".to_string())));
-    /// assert_eq!(search.next(), None);
+    /// let conf = Ini::new().header("config").comment_prefix("#");
     ///
-    /// assert_eq!(conf.section_iter("absent").count(), 0);
+    /// assert_eq!(conf.to_string(), "# config\n");
     /// ```
-    pub fn section_iter(&self, section: &str) -> SectionIter {
-        self.document.get(section).unwrap_or(&self.empty_section).iter()
+    pub fn comment_prefix(mut self, prefix: &str) -> Self {
+        self.comment_prefix = prefix.to_owned();
+        self
     }
 
-    /// Iterate over all sections in order of appearance, yielding pairs of section name
-    /// and section instance, which can be iterated over or queried similarly to Ini instances.
+    /// Get comment at the beginning of file
+    ///
+    /// When parsing, the first comment block which is separated from the following content
+    /// by an empty line is the header, so is a comment block of file without any content.
     ///
     /// # Example
     /// ```
     /// # use tini::Ini;
-    /// let conf = Ini::new().section("foo")
-    ///                      .item("item", "value")
-    ///                      .item("other", "something")
-    ///                      .section("bar")
-    ///                      .item("one", "1");
+    /// let conf = Ini::from_string("; Sample config\n\n; server settings\n[server]\nport = 80").unwrap();
     ///
-    /// for (name, section) in conf.iter() {
-    ///     match name.as_str() {
-    ///         "foo" => assert_eq!(section.iter().count(), 2),
-    ///         "bar" => assert_eq!(section.iter().count(), 1),
-    ///         _ => assert!(false),
-    ///     }
-    /// }
-    pub fn iter(&self) -> IniIter {
-        IniIter { iter: self.document.iter() }
+    /// assert_eq!(conf.get_header(), Some("Sample config"));
+    /// assert_eq!(conf.get_section_comment("server"), Some("server settings"));
+    /// ```
+    pub fn get_header(&self) -> Option<&str> {
+        self.header.as_deref()
     }
 
-    /// Iterate over all sections in arbitrary order, yielding pairs of section name and mutable
-    /// iterator over the section elements. The concrete iterator element type is
-    /// `(&'a String, SectionIterMut<'a>)`.
+    /// Get comment at the end of file
+    ///
+    /// When parsing, comment blocks after the last key or section header form the footer.
     ///
     /// # Example
     /// ```
     /// # use tini::Ini;
-    /// let mut conf = Ini::new().section("foo")
-    ///                          .item("item", "value")
-    ///                          .item("other", "something")
-    ///                          .section("bar")
-    ///                          .item("one", "1");
+    /// let conf = Ini::from_string("[server]\nport = 80\n; disabled = true\n").unwrap();
     ///
-    /// for (name, section) in conf.iter_mut() {
-    ///     for (key, val) in section.iter_mut() {
-    ///         *val = String::from("replaced");
-    ///     }
+    /// assert_eq!(conf.get_footer(), Some("disabled = true"));
+    /// assert_eq!(conf.to_string(), "[server]\nport = 80\n\n; disabled = true\n");
+    /// ```
+    pub fn get_footer(&self) -> Option<&str> {
+        self.footer.as_deref()
+    }
+
+    /// Get comment block directly above `section`
+    pub fn get_section_comment(&self, section: &str) -> Option<&str> {
+        self.get_section(section).and_then(|s| s.get_comment())
+    }
+
+    /// Get comment block directly above `key` in `section`
+    ///
+    /// When parsing, comment blocks separated by empty lines from the following key or section header
+    /// are attached to it, blocks are joined with an empty line.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[server]\n# address to listen\n# on startup\nhost = 0.0.0.0\n\n# loose\n\nport = 80").unwrap();
+    ///
+    /// assert_eq!(conf.get_key_comment("server", "host"), Some("address to listen\non startup"));
+    /// assert_eq!(conf.get_key_comment("server", "port"), Some("loose"));
+    /// ```
+    pub fn get_key_comment(&self, section: &str, key: &str) -> Option<&str> {
+        self.get_section(section).and_then(|s| s.get_key_comment(key))
+    }
+
+    /// Private method which returns section specified in last [`section()`](Ini::section) call,
+    /// creating it if necessary
+    fn current_section(&mut self) -> &mut Section {
+        let name = self.aliases.resolve(&self.document, &self.last_section_name);
+        if self.document.contains_key(name) {
+            // existing section is found without copying its name
+            return self.document.get_mut(name).expect("section exists");
+        }
+        self.section_or_insert(self.last_section_name.clone())
+    }
+
+    /// Set `value` of `key` in `section` in place, creating section if it doesn't exist,
+    /// or replace value if key already in section
+    ///
+    /// Unlike [`item()`](Ini::item) this method doesn't consume [Ini] and doesn't depend on
+    /// [`section()`](Ini::section) calls.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::new();
+    /// conf.set("server", "port", 8080);
+    /// conf.set("server", "host", "localhost");
+    /// conf.set("server", "port", 8081);
+    ///
+    /// assert_eq!(conf.to_string(), "[server]\nport = 8081\nhost = localhost\n");
+    /// ```
+    pub fn set<V>(&mut self, section: &str, key: &str, value: V)
+    where
+        V: ToIniValue,
+    {
+        self.section_or_insert(section.to_owned()).set(key, value);
+    }
+
+    /// Like [`set()`](Ini::set), but for vectors, see [`item_vec_with_sep()`](Ini::item_vec_with_sep)
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::new();
+    /// conf.set_vec_with_sep("default", "path", &["/bin", "/usr/bin"], ":");
+    ///
+    /// assert_eq!(conf.to_string(), "[default]\npath = /bin:/usr/bin\n");
+    /// ```
+    pub fn set_vec_with_sep<V>(&mut self, section: &str, key: &str, vector: &[V], sep: &str)
+    where
+        V: ToIniValue,
+    {
+        self.section_or_insert(section.to_owned()).set_vec_with_sep(key, vector, sep);
+    }
+
+    /// Equivalent of [`set_vec_with_sep(section, key, vector, ", ")`](Ini::set_vec_with_sep)
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::new();
+    /// conf.set_vec("default", "list", &[1, 2, 3]);
+    ///
+    /// assert_eq!(conf.get_vec("default", "list"), Some(vec![1, 2, 3]));
+    /// ```
+    pub fn set_vec<V>(&mut self, section: &str, key: &str, vector: &[V])
+    where
+        V: ToIniValue,
+    {
+        self.set_vec_with_sep(section, key, vector, ", ")
+    }
+
+    /// Remove `key` from `section` in place and return its raw value, if it existed
+    ///
+    /// Section itself is kept even if it becomes empty.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[one]\na = 1\nb = 2").unwrap();
+    ///
+    /// assert_eq!(conf.remove_key("one", "b"), Some("2".to_owned()));
+    /// assert_eq!(conf.remove_key("one", "b"), None);
+    /// assert_eq!(conf.to_string(), "[one]\na = 1\n");
+    /// ```
+    pub fn remove_key(&mut self, section: &str, key: &str) -> Option<String> {
+        self.section_mut(section).and_then(|s| s.remove(key))
+    }
+
+    /// Remove `section` in place and return it, if it existed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[one]\na = 1\n[two]\nb = 2").unwrap();
+    ///
+    /// let one = conf.remove_section("one").unwrap();
+    /// assert_eq!(one.get("a"), Some(1));
+    /// assert_eq!(conf.to_string(), "[two]\nb = 2\n");
+    /// ```
+    pub fn remove_section(&mut self, section: &str) -> Option<Section> {
+        self.document.remove(self.aliases.resolve(&self.document, section))
+    }
+
+    /// Rename section `old` to `new` keeping its position
+    ///
+    /// Returns `false` and leaves document unchanged if section `old` doesn't exist
+    /// or section `new` already exists.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[old]\na = 1\n[other]\nb = 2").unwrap();
+    ///
+    /// assert!(conf.rename_section("old", "new"));
+    /// assert!(!conf.rename_section("new", "other"));
+    /// assert_eq!(conf.to_string(), "[new]\na = 1\n\n[other]\nb = 2\n");
+    /// ```
+    pub fn rename_section(&mut self, old: &str, new: &str) -> bool {
+        self.aliases.rename(&mut self.document, old, new).is_some()
+    }
+
+    /// Rename key `old` in `section` to `new` keeping its position, see [Section::rename]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[server]\nhost = a\nport = 80").unwrap();
+    ///
+    /// assert!(conf.rename_key("server", "host", "address"));
+    /// assert_eq!(conf.to_string(), "[server]\naddress = a\nport = 80\n");
+    /// ```
+    pub fn rename_key(&mut self, section: &str, old: &str, new: &str) -> bool {
+        self.section_mut(section).is_some_and(|s| s.rename(old, new))
+    }
+
+    /// Move `key` from section `from` to the end of section `to`, creating it if necessary
+    ///
+    /// Returns `false` and leaves document unchanged if `key` doesn't exist in `from`
+    /// or already exists in `to`.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[general]\nport = 80\ndebug = 1").unwrap();
+    ///
+    /// assert!(conf.move_key("general", "port", "server"));
+    /// assert_eq!(conf.to_string(), "[general]\ndebug = 1\n\n[server]\nport = 80\n");
+    /// ```
+    pub fn move_key(&mut self, from: &str, key: &str, to: &str) -> bool {
+        let exists = |ini: &Self, section| ini.get_section(section).is_some_and(|s| s.get_raw(key).is_some());
+        if self.name(from) == self.name(to) {
+            return exists(self, from);
+        }
+        if !exists(self, from) || exists(self, to) {
+            return false;
+        }
+        let source = match self.section_mut(from) {
+            Some(source) => source,
+            None => return false,
+        };
+        let key = source.key(key).to_owned();
+        let kind = source.arrays.get(&key).copied();
+        let comment = source.comments.get(&key).cloned();
+        let value = source.remove(&key).unwrap_or_default();
+        let target = self.section_or_insert(to.to_owned());
+        target.insert(key.clone(), value);
+        if let Some(comment) = comment {
+            target.comments.insert(key.clone(), comment);
+        }
+        if let Some(kind) = kind {
+            target.arrays.insert(key, kind);
+        }
+        true
+    }
+
+    /// Insert `section` named `name` right before section `anchor`
+    ///
+    /// Existing section `name` is replaced and moved. Returns `false` and leaves document unchanged
+    /// if section `anchor` doesn't exist or equals to `name`.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, Section};
+    /// let mut conf = Ini::from_string("[a]\nx = 1\n[c]\ny = 2").unwrap();
+    ///
+    /// assert!(conf.insert_section_before("c", "b", Section::new()));
+    /// assert_eq!(conf.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), ["a", "b", "c"]);
+    /// ```
+    pub fn insert_section_before(&mut self, anchor: &str, name: &str, section: Section) -> bool {
+        let name = self.aliases.insert(&self.document, name.to_owned());
+        let section = section.with_case(self.is_case_insensitive());
+        self.document.insert_before(self.aliases.resolve(&self.document, anchor), name, section)
+    }
+
+    /// Insert `section` named `name` right after section `anchor`, see
+    /// [`insert_section_before()`](Ini::insert_section_before)
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, Section};
+    /// let mut conf = Ini::from_string("[a]\nx = 1\n[c]\ny = 2").unwrap();
+    ///
+    /// let mut section = Section::new();
+    /// section.set("z", 3);
+    /// assert!(conf.insert_section_after("a", "b", section));
+    /// assert_eq!(conf.to_string(), "[a]\nx = 1\n\n[b]\nz = 3\n\n[c]\ny = 2\n");
+    /// ```
+    pub fn insert_section_after(&mut self, anchor: &str, name: &str, section: Section) -> bool {
+        let name = self.aliases.insert(&self.document, name.to_owned());
+        let section = section.with_case(self.is_case_insensitive());
+        self.document.insert_after(self.aliases.resolve(&self.document, anchor), name, section)
+    }
+
+    /// Sort sections with comparator function `cmp`, which receives names and contents of two sections.
+    /// The sort is stable.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[b]\nx = 1\n[a]\ny = 2").unwrap();
+    ///
+    /// conf.sort_sections_by(|name1, _, name2, _| name1.cmp(name2));
+    /// assert_eq!(conf.to_string(), "[a]\ny = 2\n\n[b]\nx = 1\n");
+    /// ```
+    pub fn sort_sections_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&str, &Section, &str, &Section) -> Ordering,
+    {
+        self.document.sort_by(|k1, v1, k2, v2| cmp(k1, v1, k2, v2));
+    }
+
+    /// Compare documents ignoring order of sections and keys
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let a = Ini::from_string("[a]\nx = 1\ny = 2\n[b]\nz = 3").unwrap();
+    /// let b = Ini::from_string("[b]\nz = 3\n[a]\ny = 2\nx = 1").unwrap();
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.eq_unordered(&b));
+    /// ```
+    pub fn eq_unordered(&self, other: &Ini) -> bool {
+        self.document.len() == other.document.len()
+            && self
+                .document
+                .iter()
+                .all(|(name, section)| other.get_section(name).is_some_and(|other| section.eq_unordered(other)))
+    }
+
+    /// Rewrite document in canonical form
+    ///
+    /// - sections and keys are sorted using `order`;
+    /// - boolean words `yes`/`no`, `on`/`off` in any case are written as `true`/`false`;
+    /// - every value with unquoted `,` is a list, it is written with `", "` separator
+    ///   and booleans are normalized in its elements, e.g. `1,2 ,3` becomes `1, 2, 3`.
+    ///
+    /// Values are not checked against types, so free text is normalized by the same rules:
+    /// `a,b` and `a, b` as well as `Yes` and `true` have the same canonical form.
+    ///
+    /// Duplicate sections and keys don't survive parsing: they are merged and the last value wins,
+    /// so canonical form contains every section and key once. Comments are kept.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, SortOrder};
+    /// let mut conf = Ini::from_string("[b]\nkey10 = On\nkey2 = 1,2 ,3\n[a]\nx = 1\n[b]\nkey1 = no").unwrap();
+    ///
+    /// conf.canonicalize(SortOrder::Natural);
+    /// assert_eq!(conf.to_string(), "[a]\nx = 1\n\n[b]\nkey1 = false\nkey2 = 1, 2, 3\nkey10 = true\n");
+    /// ```
+    pub fn canonicalize(&mut self, order: SortOrder) {
+        self.sort_sections_by(|a, _, b, _| order.compare(a, b));
+        for (_, section) in self.iter_mut() {
+            section.canonicalize(order);
+        }
+    }
+
+    /// Hash of document content which is stable across runs, platforms and crate versions
+    ///
+    /// Documents with the same canonical form (see [`canonicalize()`](Ini::canonicalize)) have
+    /// the same hash regardless of section and key order. Comments are not hashed.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let a = Ini::from_string("[s]\nflag = yes\nlist = 1,2").unwrap();
+    /// let b = Ini::from_string("; generated\n[s]\nlist = 1, 2\nflag = true").unwrap();
+    /// let c = Ini::from_string("[s]\nflag = yes\nlist = 1, 3").unwrap();
+    ///
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    /// assert_ne!(a.content_hash(), c.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let order = SortOrder::Alphabetical;
+        let mut hasher = canonical::Fnv1a::new();
+        let mut sections: Vec<(&String, &Section)> = self.iter().collect();
+        sections.sort_by(|(a, _), (b, _)| order.compare(a, b));
+        hasher.write_len(sections.len());
+        for (name, section) in sections {
+            let mut keys: Vec<(&String, &String)> = section.iter().collect();
+            keys.sort_by(|(a, _), (b, _)| order.compare(a, b));
+            hasher.write_str(name);
+            hasher.write_len(keys.len());
+            for (key, value) in keys {
+                hasher.write_str(key);
+                hasher.write_str(&canonical::normalize(value, section.arrays.get(key.as_str()).copied()));
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Get entry of `key` in `section` for in-place manipulation
+    ///
+    /// Missing section and key are created only when value is inserted, both are appended to the end.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[stats]\nruns = 1").unwrap();
+    ///
+    /// conf.entry("stats", "runs").and_modify_as(|runs: u32| runs + 1).unwrap();
+    /// conf.entry("stats", "errors").or_insert(0);
+    /// conf.entry("user", "name").or_insert_with(|| "guest");
+    ///
+    /// assert_eq!(conf.to_string(), "[stats]\nruns = 2\nerrors = 0\n\n[user]\nname = guest\n");
+    /// ```
+    pub fn entry(&mut self, section: &str, key: &str) -> Entry<'_> {
+        let aliases = Aliases::new(self.is_case_insensitive());
+        match self.document.entry(self.aliases.insert(&self.document, section.to_owned())) {
+            map::Entry::Occupied(entry) => entry.into_mut().entry(key),
+            map::Entry::Vacant(entry) => Entry {
+                inner: EntryInner::VacantSection(entry, Box::new(Section::with_aliases(aliases)), key.to_owned()),
+            },
+        }
+    }
+
+    /// Private method which get value by `key` from `section`
+    fn get_raw(&self, section: &str, key: &str) -> Option<&String> {
+        self.document.get(self.name(section)).and_then(|s| s.get_raw(key))
+    }
+
+    /// Get scalar value of key in section.
+    ///
+    /// - output type `T` must implement [FromIniValue] trait for auto conversion
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\none = 1").unwrap();
+    ///
+    /// let value: Option<u8> = conf.get("section", "one");
+    ///
+    /// assert_eq!(value, Some(1));
+    /// ```
+    pub fn get<T>(&self, section: &str, key: &str) -> Option<T>
+    where
+        T: FromIniValue,
+    {
+        self.get_raw(section, key).and_then(|x| T::from_ini_value(x).ok())
+    }
+
+    /// Get vector value of `key` in `section`. Value should use `,` as separator.
+    ///
+    /// The function returns [None](Option::None) if one of the elements can not be parsed.
+    ///
+    /// - output type `T` must implement [FromIniValue] trait for auto conversion
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\nlist = 1, 2, 3, 4").unwrap();
+    ///
+    /// let value: Option<Vec<u8>> = conf.get_vec("section", "list");
+    ///
+    /// assert_eq!(value, Some(vec![1, 2, 3, 4]));
+    /// ```
+    pub fn get_vec<T>(&self, section: &str, key: &str) -> Option<Vec<T>>
+    where
+        T: FromIniValue,
+    {
+        self.get_vec_with_sep(section, key, ",")
+    }
+
+    /// Get vector value of `key` in `section` separated by `sep` string.
+    ///
+    /// The function returns [None](Option::None) if one of the elements can not be parsed or not found.
+    ///
+    /// - output type `T` must implement [FromIniValue] trait for auto conversion
+    ///
+    /// List syntax:
+    /// - whitespace around elements is ignored;
+    /// - `sep` consisting only of whitespace (e.g. `" "`) splits on any run of whitespace;
+    /// - elements may be quoted (`"a, b"`), inside quotes `\"` and `\\` are escapes for `"` and `\`;
+    /// - separator, `"` and `\` may be escaped with `\` in unquoted elements (`a\, b`);
+    /// - empty value is an empty list, `""` is a list with one empty string.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\nlist = 1|2|3|4\nwords = \"a, b\", c\\, d\nspaces = a  b c\nempty =").unwrap();
+    ///
+    /// let value: Option<Vec<u8>> = conf.get_vec_with_sep("section", "list", "|");
+    /// assert_eq!(value, Some(vec![1, 2, 3, 4]));
+    ///
+    /// let words: Vec<String> = conf.get_vec_with_sep("section", "words", ",").unwrap();
+    /// assert_eq!(words, ["a, b", "c, d"]);
+    ///
+    /// let spaces: Vec<String> = conf.get_vec_with_sep("section", "spaces", " ").unwrap();
+    /// assert_eq!(spaces, ["a", "b", "c"]);
+    ///
+    /// let empty: Vec<u8> = conf.get_vec_with_sep("section", "empty", ",").unwrap();
+    /// assert!(empty.is_empty());
+    /// ```
+    pub fn get_vec_with_sep<T>(&self, section: &str, key: &str, sep: &str) -> Option<Vec<T>>
+    where
+        T: FromIniValue,
+    {
+        self.get_raw(section, key).and_then(|x| parse_list(x, sep).ok())
+    }
+
+    /// Private method which get value by `key` from `section` or report what is missing
+    fn lookup(&self, section: &str, key: &str) -> Result<&String, GetError> {
+        let data = self.document.get(self.name(section)).ok_or_else(|| GetError::MissingSection(section.to_owned()))?;
+        data.get_raw(key).ok_or_else(|| GetError::MissingKey { section: section.to_owned(), key: key.to_owned() })
+    }
+
+    /// Fallible version of [`get()`](Ini::get): missing key is reported as `Ok(None)`,
+    /// while missing section and values which cannot be converted to `T` are errors.
+    ///
+    /// - output type `T` must implement [FromIniValue] trait for auto conversion
+    ///
+    /// # Errors
+    /// This function will return [GetError::MissingSection] if section doesn't exist
+    /// and [GetError::Conversion] if value cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{GetError, Ini};
+    /// let conf = Ini::from_string("[server]\nport = eighty").unwrap();
+    ///
+    /// assert_eq!(conf.try_get::<u16>("server", "host"), Ok(None));
+    /// assert_eq!(conf.try_get::<u16>("client", "port"), Err(GetError::MissingSection("client".to_owned())));
+    /// match conf.try_get::<u16>("server", "port") {
+    ///     Err(GetError::Conversion { value, type_name, .. }) => {
+    ///         assert_eq!(value, "eighty");
+    ///         assert_eq!(type_name, "u16");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn try_get<T>(&self, section: &str, key: &str) -> Result<Option<T>, GetError>
+    where
+        T: FromIniValue,
+    {
+        optional(self.require(section, key))
+    }
+
+    /// Like [`try_get()`](Ini::try_get), but for vectors with `,` as separator
+    ///
+    /// # Errors
+    /// This function will return [GetError::MissingSection] if section doesn't exist
+    /// and [GetError::Conversion] if one of the elements cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\nlist = 1, 2, x").unwrap();
+    ///
+    /// assert!(conf.try_get_vec::<u8>("section", "list").is_err());
+    /// assert_eq!(conf.try_get_vec::<String>("section", "list").unwrap().unwrap(), ["1", "2", "x"]);
+    /// ```
+    pub fn try_get_vec<T>(&self, section: &str, key: &str) -> Result<Option<Vec<T>>, GetError>
+    where
+        T: FromIniValue,
+    {
+        self.try_get_vec_with_sep(section, key, ",")
+    }
+
+    /// Like [`try_get()`](Ini::try_get), but for vectors separated by `sep` string
+    ///
+    /// # Errors
+    /// This function will return [GetError::MissingSection] if section doesn't exist
+    /// and [GetError::Conversion] if one of the elements cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\nlist = 1|2|3").unwrap();
+    ///
+    /// let value: Option<Vec<u8>> = conf.try_get_vec_with_sep("section", "list", "|").unwrap();
+    /// assert_eq!(value, Some(vec![1, 2, 3]));
+    /// ```
+    pub fn try_get_vec_with_sep<T>(&self, section: &str, key: &str, sep: &str) -> Result<Option<Vec<T>>, GetError>
+    where
+        T: FromIniValue,
+    {
+        optional(self.lookup(section, key).and_then(|x| convert_vec(x, sep)))
+    }
+
+    /// Get scalar value of mandatory key in section.
+    ///
+    /// Unlike [`try_get()`](Ini::try_get), missing section or key is an error too.
+    ///
+    /// # Errors
+    /// This function will return [GetError::MissingSection] or [GetError::MissingKey] if value doesn't exist
+    /// and [GetError::Conversion] if value cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{GetError, Ini};
+    /// let conf = Ini::from_string("[server]\nport = 8080").unwrap();
+    ///
+    /// assert_eq!(conf.require::<u16>("server", "port"), Ok(8080));
+    /// assert_eq!(conf.require::<u16>("client", "port"), Err(GetError::MissingSection("client".to_owned())));
+    /// assert_eq!(
+    ///     conf.require::<String>("server", "host"),
+    ///     Err(GetError::MissingKey { section: "server".to_owned(), key: "host".to_owned() })
+    /// );
+    /// ```
+    pub fn require<T>(&self, section: &str, key: &str) -> Result<T, GetError>
+    where
+        T: FromIniValue,
+    {
+        self.lookup(section, key).and_then(|x| convert(x))
+    }
+
+    /// Like [`require()`](Ini::require), but for vectors with `,` as separator
+    ///
+    /// # Errors
+    /// Same as [`require()`](Ini::require)
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\nlist = 1, 2, 3").unwrap();
+    ///
+    /// assert_eq!(conf.require_vec::<u8>("section", "list").unwrap(), [1, 2, 3]);
+    /// assert!(conf.require_vec::<u8>("section", "other").is_err());
+    /// ```
+    pub fn require_vec<T>(&self, section: &str, key: &str) -> Result<Vec<T>, GetError>
+    where
+        T: FromIniValue,
+    {
+        self.lookup(section, key).and_then(|x| convert_vec(x, ","))
+    }
+
+    /// Get range list value of `key` in `section` expanded to vector, e.g. `0-3, 8` is read as `[0, 1, 2, 3, 8]`.
+    /// Ranges are separated by `,` with the same syntax as in [`get_vec()`](Ini::get_vec).
+    ///
+    /// The function returns [None](Option::None) if one of the ranges can not be parsed,
+    /// is reversed (`5-3`) or overflows the output type, or if the list expands to more
+    /// than 1 048 576 integers.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[cpu]\npinned = 0-3,8,10-11\nports = 8000-8002").unwrap();
+    ///
+    /// let pinned: Vec<u8> = conf.get_ranges("cpu", "pinned").unwrap();
+    /// assert_eq!(pinned, [0, 1, 2, 3, 8, 10, 11]);
+    /// assert_eq!(conf.get_ranges::<u16>("cpu", "ports"), Some(vec![8000, 8001, 8002]));
+    /// assert_eq!(conf.get_ranges::<u8>("cpu", "ports"), None);
+    /// ```
+    pub fn get_ranges<T>(&self, section: &str, key: &str) -> Option<Vec<T>>
+    where
+        T: RangeInteger,
+    {
+        self.get_ranges_with_sep(section, key, ",")
+    }
+
+    /// Like [`get_ranges()`](Ini::get_ranges), but with `sep` between ranges
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[net]\nvlans = 10-12 20").unwrap();
+    ///
+    /// assert_eq!(conf.get_ranges_with_sep("net", "vlans", " "), Some(vec![10u16, 11, 12, 20]));
+    /// ```
+    pub fn get_ranges_with_sep<T>(&self, section: &str, key: &str, sep: &str) -> Option<Vec<T>>
+    where
+        T: RangeInteger,
+    {
+        self.get_raw(section, key).and_then(|x| convert_ranges(x, sep).ok())
+    }
+
+    /// Like [`try_get()`](Ini::try_get), but for range lists, see [`get_ranges()`](Ini::get_ranges)
+    ///
+    /// # Errors
+    /// This function will return [GetError::MissingSection] if section doesn't exist
+    /// and [GetError::Conversion] with the failed range as value
+    /// if one of the ranges cannot be parsed, is reversed or overflows the output type,
+    /// or if the list expands to more than 1 048 576 integers
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{GetError, Ini};
+    /// let conf = Ini::from_string("[cpu]\npinned = 0-3, 7-5").unwrap();
+    ///
+    /// match conf.try_get_ranges::<u8>("cpu", "pinned") {
+    ///     Err(GetError::Conversion { value, message, .. }) => {
+    ///         assert_eq!(value, "7-5");
+    ///         assert_eq!(message, "range `7-5` is reversed");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn try_get_ranges<T>(&self, section: &str, key: &str) -> Result<Option<Vec<T>>, GetError>
+    where
+        T: RangeInteger,
+    {
+        optional(self.lookup(section, key).and_then(|x| convert_ranges(x, ",")))
+    }
+
+    /// Get value of `key` in `section` translated to `locale`, see [Section::get_localized]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string([
+    ///     "[Desktop Entry]",
+    ///     "Name = Editor",
+    ///     "Name[de] = Bearbeiter",
+    ///     "Name[de_DE@euro] = Euro-Bearbeiter",
+    /// ].join("\n")).unwrap();
+    ///
+    /// let name = |locale| conf.get_localized::<String>("Desktop Entry", "Name", locale).unwrap();
+    /// assert_eq!(name("de_DE@euro"), "Euro-Bearbeiter");
+    /// assert_eq!(name("de_AT"), "Bearbeiter");
+    /// assert_eq!(name("en_US"), "Editor");
+    /// ```
+    pub fn get_localized<T>(&self, section: &str, key: &str, locale: &str) -> Option<T>
+    where
+        T: FromIniValue,
+    {
+        self.document.get(self.name(section)).and_then(|s| s.get_localized(key, locale))
+    }
+
+    /// Iterate over all translations of `key` in `section`, see [Section::translations]
+    ///
+    /// If section with given name doesn't exist in document, method returns empty iterator
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[app]\nName = Editor\nName[de] = Bearbeiter").unwrap();
+    ///
+    /// let mut translations = conf.translations("app", "Name");
+    /// assert_eq!(translations.next(), Some(("de", &"Bearbeiter".to_owned())));
+    /// assert_eq!(translations.next(), None);
+    /// ```
+    pub fn translations<'a>(&'a self, section: &str, key: &'a str) -> Translations<'a> {
+        self.document.get(self.name(section)).unwrap_or(&self.empty_section).translations(key)
+    }
+
+    /// Get inline map value of `key` in `section`, e.g. `weights = cpu:2, io:1`.
+    /// Key and value are separated by the first `:` outside of quotes, entries are separated by `,`
+    /// with the same syntax as in [`get_vec()`](Ini::get_vec), e.g. `"a:b":1, url:http://host`.
+    ///
+    /// The function returns [None](Option::None) if one of the entries can not be parsed.
+    ///
+    /// - key and value types `K` and `V` must implement [FromIniValue] trait for auto conversion
+    /// - output type `M` may be any collection of pairs, e.g. [HashMap](std::collections::HashMap),
+    ///   [BTreeMap](std::collections::BTreeMap) or [Vec]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// use std::collections::{BTreeMap, HashMap};
+    ///
+    /// let conf = Ini::from_string("[limits]\nweights = cpu:2, io:1, net:3").unwrap();
+    ///
+    /// let weights: HashMap<String, u32> = conf.get_map("limits", "weights").unwrap();
+    /// assert_eq!(weights["io"], 1);
+    ///
+    /// let sorted = conf.get_map::<String, u32, BTreeMap<_, _>>("limits", "weights").unwrap();
+    /// assert_eq!(sorted.keys().collect::<Vec<_>>(), ["cpu", "io", "net"]);
+    /// ```
+    pub fn get_map<K, V, M>(&self, section: &str, key: &str) -> Option<M>
+    where
+        K: FromIniValue,
+        V: FromIniValue,
+        M: FromIterator<(K, V)>,
+    {
+        self.get_map_with_sep(section, key, ":", ",")
+    }
+
+    /// Like [`get_map()`](Ini::get_map), but with `pair_sep` between key and value and `entry_sep` between entries
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// use std::collections::HashMap;
+    ///
+    /// let conf = Ini::from_string("[default]\nmap = a=1 | b=2").unwrap();
+    ///
+    /// let map: HashMap<String, u8> = conf.get_map_with_sep("default", "map", "=", "|").unwrap();
+    /// assert_eq!(map["b"], 2);
+    /// ```
+    pub fn get_map_with_sep<K, V, M>(&self, section: &str, key: &str, pair_sep: &str, entry_sep: &str) -> Option<M>
+    where
+        K: FromIniValue,
+        V: FromIniValue,
+        M: FromIterator<(K, V)>,
+    {
+        self.get_raw(section, key).and_then(|x| convert_map(x, pair_sep, entry_sep).ok())
+    }
+
+    /// Like [`try_get()`](Ini::try_get), but for inline maps, see [`get_map()`](Ini::get_map)
+    ///
+    /// # Errors
+    /// This function will return [GetError::MissingSection] if section doesn't exist
+    /// and [GetError::Conversion] with the failed entry as value
+    /// if one of the entries cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{GetError, Ini};
+    /// use std::collections::HashMap;
+    ///
+    /// let conf = Ini::from_string("[limits]\nweights = cpu:2, io:x").unwrap();
+    ///
+    /// match conf.try_get_map::<String, u32, HashMap<_, _>>("limits", "weights") {
+    ///     Err(GetError::Conversion { value, .. }) => assert_eq!(value, "io:x"),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn try_get_map<K, V, M>(&self, section: &str, key: &str) -> Result<Option<M>, GetError>
+    where
+        K: FromIniValue,
+        V: FromIniValue,
+        M: FromIterator<(K, V)>,
+    {
+        self.try_get_map_with_sep(section, key, ":", ",")
+    }
+
+    /// Like [`try_get_map()`](Ini::try_get_map), but with `pair_sep` between key and value
+    /// and `entry_sep` between entries
+    ///
+    /// # Errors
+    /// Same as [`try_get_map()`](Ini::try_get_map)
+    pub fn try_get_map_with_sep<K, V, M>(
+        &self, section: &str, key: &str, pair_sep: &str, entry_sep: &str,
+    ) -> Result<Option<M>, GetError>
+    where
+        K: FromIniValue,
+        V: FromIniValue,
+        M: FromIterator<(K, V)>,
+    {
+        optional(self.lookup(section, key).and_then(|x| convert_map(x, pair_sep, entry_sep)))
+    }
+
+    /// Get `section` by name or [None] if it doesn't exist
+    ///
+    /// See also `conf["section"]` indexing, which panics on missing section.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[server]\nport = 8080").unwrap();
+    ///
+    /// assert_eq!(conf.get_section("server").and_then(|s| s.get("port")), Some(8080));
+    /// assert!(conf.get_section("client").is_none());
+    /// ```
+    pub fn get_section(&self, section: &str) -> Option<&Section> {
+        self.document.get(self.name(section))
+    }
+
+    /// Get mutable reference to `section` or [None] if it doesn't exist
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[server]\nport = 8080").unwrap();
+    ///
+    /// if let Some(section) = conf.section_mut("server") {
+    ///     section.insert("host".to_owned(), "localhost".to_owned());
+    /// }
+    /// assert_eq!(conf.to_string(), "[server]\nport = 8080\nhost = localhost\n");
+    /// ```
+    pub fn section_mut(&mut self, section: &str) -> Option<&mut Section> {
+        self.document.get_mut(self.aliases.resolve(&self.document, section))
+    }
+
+    /// An iterator visiting all key-value pairs of a section in order of appearance.
+    ///
+    /// If section with given name doesn't exist in document, method returns empty iterator
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string(["[search]",
+    ///                              "g = google.com",
+    ///                              "dd = duckduckgo.com"].join("\n")).unwrap();
+    ///
+    /// let mut search = conf.section_iter("search");
+    /// assert_eq!(search.next(), Some((&"g".to_string(), &"google.com".to_string())));
+    /// assert_eq!(search.next(), Some((&"dd".to_string(), &"duckduckgo.com".to_string())));
+    /// assert_eq!(search.next(), None);
+    ///
+    /// assert_eq!(conf.section_iter("absent").count(), 0);
+    /// ```
+    pub fn section_iter(&self, section: &str) -> SectionIter<'_> {
+        self.document.get(self.name(section)).unwrap_or(&self.empty_section).iter()
+    }
+
+    /// Iterate over all sections in order of appearance, yielding pairs of section name
+    /// and section instance, which can be iterated over or queried similarly to Ini instances.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::new().section("foo")
+    ///                      .item("item", "value")
+    ///                      .item("other", "something")
+    ///                      .section("bar")
+    ///                      .item("one", "1");
+    ///
+    /// for (name, section) in conf.iter() {
+    ///     match name.as_str() {
+    ///         "foo" => assert_eq!(section.iter().count(), 2),
+    ///         "bar" => assert_eq!(section.iter().count(), 1),
+    ///         _ => assert!(false),
+    ///     }
+    /// }
+    pub fn iter(&self) -> IniIter<'_> {
+        IniIter { iter: self.document.iter() }
+    }
+
+    /// Iterate over all sections in order of appearance, yielding pairs of section name and mutable
+    /// iterator over the section elements. The concrete iterator element type is
+    /// `(&'a String, SectionIterMut<'a>)`.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::new().section("foo")
+    ///                          .item("item", "value")
+    ///                          .item("other", "something")
+    ///                          .section("bar")
+    ///                          .item("one", "1");
+    ///
+    /// for (name, section) in conf.iter_mut() {
+    ///     for (key, val) in section.iter_mut() {
+    ///         *val = String::from("replaced");
+    ///     }
+    /// }
+    ///
+    /// for (name, section) in conf.iter() {
+    ///     for (key, val) in section.iter() {
+    ///         assert_eq!(val.as_str(), "replaced");
+    ///     }
+    /// }
+    pub fn iter_mut(&mut self) -> IniIterMut<'_> {
+        IniIterMut { iter: self.document.iter_mut() }
+    }
+}
+
+impl fmt::Display for Ini {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writer::write(self, f, &WriteOptions::default())
+    }
+}
+
+impl Default for Ini {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for Ini {
+    fn eq(&self, other: &Self) -> bool {
+        self.document == other.document
+    }
+}
+
+impl Eq for Ini {}
+
+impl IntoIterator for Ini {
+    type Item = (String, Section);
+    type IntoIter = IniIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IniIntoIter { iter: self.document.into_iter() }
+    }
+}
+
+impl<'a> IntoIterator for &'a Ini {
+    type Item = (&'a String, &'a Section);
+    type IntoIter = IniIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Collect sections into document, sections with the same name are merged
+///
+/// # Example
+/// ```
+/// # use tini::{Ini, Section};
+/// let mut server = Section::new();
+/// server.set("port", 80);
+///
+/// let conf: Ini = vec![("server".to_owned(), server)].into_iter().collect();
+/// assert_eq!(conf.to_string(), "[server]\nport = 80\n");
+/// ```
+impl FromIterator<(String, Section)> for Ini {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Section)>,
+    {
+        let mut ini = Ini::new();
+        ini.extend(iter);
+        ini
+    }
+}
+
+/// Append sections to the end of document, sections which already exist are merged
+/// like duplicate sections during parsing: keys are appended and values of existing keys are replaced
+///
+/// # Example
+/// ```
+/// # use tini::Ini;
+/// let mut conf = Ini::from_string("[a]\nx = 1").unwrap();
+/// let other = Ini::from_string("[b]\ny = 2\n[a]\nx = 3\nz = 4").unwrap();
+///
+/// conf.extend(other);
+/// assert_eq!(conf.to_string(), "[a]\nx = 3\nz = 4\n\n[b]\ny = 2\n");
+/// ```
+impl Extend<(String, Section)> for Ini {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (String, Section)>,
+    {
+        for (name, section) in iter {
+            let name = self.aliases.insert(&self.document, name);
+            let insensitive = self.aliases.is_insensitive();
+            match self.document.entry(name) {
+                map::Entry::Occupied(entry) => entry.into_mut().merge(section),
+                map::Entry::Vacant(entry) => {
+                    entry.insert(section.with_case(insensitive));
+                }
+            }
+        }
+    }
+}
+
+/// Convert nested maps of sections and keys into document, order follows iteration order of `map`
+///
+/// # Example
+/// ```
+/// # use tini::Ini;
+/// use std::collections::BTreeMap;
+///
+/// let mut section = BTreeMap::new();
+/// section.insert("b".to_owned(), "2".to_owned());
+/// section.insert("a".to_owned(), "1".to_owned());
+/// let mut map = BTreeMap::new();
+/// map.insert("s".to_owned(), section);
+///
+/// let conf = Ini::from(map.clone());
+/// assert_eq!(conf.to_string(), "[s]\na = 1\nb = 2\n");
+/// assert_eq!(BTreeMap::from(conf), map);
+/// ```
+impl From<BTreeMap<String, BTreeMap<String, String>>> for Ini {
+    fn from(map: BTreeMap<String, BTreeMap<String, String>>) -> Self {
+        map.into_iter().map(|(name, section)| (name, section.into_iter().collect())).collect()
+    }
+}
+
+impl From<HashMap<String, HashMap<String, String>>> for Ini {
+    fn from(map: HashMap<String, HashMap<String, String>>) -> Self {
+        map.into_iter().map(|(name, section)| (name, section.into_iter().collect())).collect()
+    }
+}
+
+impl From<Ini> for BTreeMap<String, BTreeMap<String, String>> {
+    fn from(ini: Ini) -> Self {
+        ini.into_iter().map(|(name, section)| (name, section.into_iter().collect())).collect()
+    }
+}
+
+/// Convert document into nested maps of sections and keys
+///
+/// # Example
+/// ```
+/// # use tini::Ini;
+/// use std::collections::HashMap;
+///
+/// let conf = Ini::from_string("[s]\na = 1").unwrap();
+/// let map = HashMap::<String, HashMap<String, String>>::from(conf);
+///
+/// assert_eq!(map["s"]["a"], "1");
+/// ```
+impl From<Ini> for HashMap<String, HashMap<String, String>> {
+    fn from(ini: Ini) -> Self {
+        ini.into_iter().map(|(name, section)| (name, section.into_iter().collect())).collect()
+    }
+}
+
+/// Access section by name
+///
+/// # Panics
+/// Panics if section doesn't exist, use [`get_section()`](Ini::get_section) to avoid it
+///
+/// # Example
+/// ```
+/// # use tini::Ini;
+/// let mut conf = Ini::from_string("[server]\nport = 8080").unwrap();
+///
+/// assert_eq!(conf["server"]["port"], "8080");
+/// conf["server"]["port"] = "8081".to_owned();
+/// assert_eq!(conf.get("server", "port"), Some(8081));
+/// ```
+impl Index<&str> for Ini {
+    type Output = Section;
+
+    fn index(&self, section: &str) -> &Section {
+        match self.document.get(self.name(section)) {
+            Some(value) => value,
+            None => panic!("Section `{}` not found", section),
+        }
+    }
+}
+
+/// Access section by name mutably
+///
+/// # Panics
+/// Panics if section doesn't exist, use [`section_mut()`](Ini::section_mut) to avoid it
+impl IndexMut<&str> for Ini {
+    fn index_mut(&mut self, section: &str) -> &mut Section {
+        match self.document.get_mut(self.aliases.resolve(&self.document, section)) {
+            Some(value) => value,
+            None => panic!("Section `{}` not found", section),
+        }
+    }
+}
+
+/// An iterator over the sections of an ini document
+pub struct IniIter<'a> {
+    #[doc(hidden)]
+    iter: ordered_hashmap::Iter<'a, String, Section>,
+}
+
+impl<'a> Iterator for IniIter<'a> {
+    type Item = (&'a String, &'a Section);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+/// An owning iterator over the sections of an ini document
+pub struct IniIntoIter {
+    #[doc(hidden)]
+    iter: ordered_hashmap::IntoIter<String, Section>,
+}
+
+impl Iterator for IniIntoIter {
+    type Item = (String, Section);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+/// A mutable iterator over the sections of an ini document
+pub struct IniIterMut<'a> {
+    #[doc(hidden)]
+    iter: ordered_hashmap::IterMut<'a, String, Section>,
+}
+
+impl<'a> Iterator for IniIterMut<'a> {
+    type Item = (&'a String, &'a mut Section);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+/// Section of INI-file data
+///
+/// Two sections are equal if they have equal key-value pairs in the same order,
+/// use [`eq_unordered()`](Section::eq_unordered) to ignore the order. Comments are not compared.
+#[derive(Debug, Clone)]
+pub struct Section {
+    inner: OrderedHashMap<String, String>,
+    /// keys which were parsed from php-style arrays
+    arrays: HashMap<String, ArrayKind>,
+    /// spelling of keys in case-insensitive mode
+    aliases: Aliases,
+    /// comment above section header
+    comment: Option<String>,
+    /// comments above keys
+    comments: HashMap<String, String>,
+}
+
+pub struct SectionIter<'a> {
+    #[doc(hidden)]
+    iter: ordered_hashmap::Iter<'a, String, String>,
+}
+
+impl<'a> Iterator for SectionIter<'a> {
+    type Item = (&'a String, &'a String);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+/// An iterator over translations of a key, see [Section::translations]
+pub struct Translations<'a> {
+    #[doc(hidden)]
+    iter: SectionIter<'a>,
+    key: &'a str,
+    insensitive: bool,
+}
+
+impl<'a> Iterator for Translations<'a> {
+    type Item = (&'a str, &'a String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, insensitive) = (self.key, self.insensitive);
+        let matches = |name: &str| name == key || insensitive && name.to_lowercase() == key.to_lowercase();
+        self.iter.find_map(|(k, v)| match split_array_key(k) {
+            Some((name, locale)) if matches(name) && !locale.is_empty() => Some((locale, v)),
+            _ => None,
+        })
+    }
+}
+
+pub struct SectionIterMut<'a> {
+    #[doc(hidden)]
+    iter: ordered_hashmap::IterMut<'a, String, String>,
+    #[doc(hidden)]
+    arrays: &'a mut HashMap<String, ArrayKind>,
+}
+
+impl<'a> Iterator for SectionIterMut<'a> {
+    type Item = (&'a String, &'a mut String);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.iter.next()?;
+        // value may be changed, so it's no longer known to be php-style array
+        invalidate(self.arrays, key);
+        Some((key, value))
+    }
+}
+
+/// An owning iterator over the key-value pairs of a section
+pub struct SectionIntoIter {
+    #[doc(hidden)]
+    iter: ordered_hashmap::IntoIter<String, String>,
+}
+
+impl Iterator for SectionIntoIter {
+    type Item = (String, String);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+/// An iterator over the keys of a section, see [Section::keys]
+pub struct SectionKeys<'a> {
+    #[doc(hidden)]
+    iter: map::Keys<'a, String, String>,
+}
+
+impl<'a> Iterator for SectionKeys<'a> {
+    type Item = &'a String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+/// A view into a single key of [Ini] or [Section], which may be missing,
+/// see [Ini::entry] and [Section::entry]
+pub struct Entry<'a> {
+    #[doc(hidden)]
+    inner: EntryInner<'a>,
+}
+
+enum EntryInner<'a> {
+    Occupied(map::OccupiedEntry<'a, String, String>, &'a mut HashMap<String, ArrayKind>),
+    VacantKey(map::VacantEntry<'a, String, String>),
+    VacantSection(map::VacantEntry<'a, String, Section>, Box<Section>, String),
+}
+
+impl<'a> Entry<'a> {
+    /// Name of the key of this entry
+    pub fn key(&self) -> &str {
+        match &self.inner {
+            EntryInner::Occupied(entry, _) => entry.key(),
+            EntryInner::VacantKey(entry) => entry.key(),
+            EntryInner::VacantSection(_, _, key) => key,
+        }
+    }
+
+    /// Raw value of this entry or [None] if key is missing
+    pub fn get_raw(&self) -> Option<&String> {
+        match &self.inner {
+            EntryInner::Occupied(entry, _) => Some(entry.get()),
+            _ => None,
+        }
+    }
+
+    /// Insert `default` if key is missing and return mutable reference to raw value
+    pub fn or_insert<V>(self, default: V) -> &'a mut String
+    where
+        V: ToIniValue,
+    {
+        self.or_insert_with(|| default)
+    }
+
+    /// Insert result of `default` function if key is missing and return mutable reference to raw value
+    pub fn or_insert_with<F, V>(self, default: F) -> &'a mut String
+    where
+        F: FnOnce() -> V,
+        V: ToIniValue,
+    {
+        match self.inner {
+            EntryInner::Occupied(entry, arrays) => {
+                invalidate(arrays, entry.key());
+                entry.into_mut()
+            }
+            EntryInner::VacantKey(entry) => entry.insert(default().to_ini_value()),
+            EntryInner::VacantSection(entry, section, key) => {
+                entry.insert(*section).entry(&key).or_insert_with(default)
+            }
+        }
+    }
+
+    /// Modify raw value in place if key exists
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut String),
+    {
+        if let EntryInner::Occupied(entry, arrays) = &mut self.inner {
+            invalidate(arrays, entry.key());
+            f(entry.get_mut());
+        }
+        self
+    }
+
+    /// Like [`or_insert()`](Entry::or_insert), but converts resulting value to `T`
+    ///
+    /// # Errors
+    /// [GetError::Conversion] if existing value cannot be converted to `T`
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[net]\ntimeout = 30").unwrap();
+    ///
+    /// assert_eq!(conf.entry("net", "timeout").or_insert_as(10u32), Ok(30));
+    /// assert_eq!(conf.entry("net", "retries").or_insert_as(3u32), Ok(3));
+    /// ```
+    pub fn or_insert_as<T>(self, default: T) -> Result<T, GetError>
+    where
+        T: FromIniValue + ToIniValue,
+    {
+        convert(self.or_insert(default))
+    }
+
+    /// Like [`and_modify()`](Entry::and_modify), but converts existing value to `T`
+    /// and stores result of `f` back
+    ///
+    /// # Errors
+    /// [GetError::Conversion] if existing value cannot be converted to `T`, value is left unchanged
+    pub fn and_modify_as<T, F>(mut self, f: F) -> Result<Self, GetError>
+    where
+        T: FromIniValue + ToIniValue,
+        F: FnOnce(T) -> T,
+    {
+        if let EntryInner::Occupied(entry, arrays) = &mut self.inner {
+            let value = f(convert(entry.get())?).to_ini_value();
+            invalidate(arrays, entry.key());
+            *entry.get_mut() = value;
+        }
+        Ok(self)
+    }
+}
+
+impl Section {
+    pub fn new() -> Self {
+        Section::with_aliases(Aliases::default())
+    }
+
+    /// Create an empty section with case-insensitive keys, see [Ini::new_case_insensitive]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Section;
+    /// let mut section = Section::new_case_insensitive();
+    /// section.set("Port", 80);
+    /// section.set("PORT", 8080);
+    ///
+    /// assert_eq!(section.get("port"), Some(8080));
+    /// assert_eq!(section.keys().collect::<Vec<_>>(), ["Port"]);
+    /// ```
+    pub fn new_case_insensitive() -> Self {
+        Section::with_aliases(Aliases::new(true))
+    }
+
+    fn with_aliases(aliases: Aliases) -> Self {
+        Section {
+            inner: OrderedHashMap::new(),
+            arrays: HashMap::new(),
+            aliases,
+            comment: None,
+            comments: HashMap::new(),
+        }
+    }
+
+    /// Returns `true` if keys of section are case-insensitive
+    pub fn is_case_insensitive(&self) -> bool {
+        self.aliases.is_insensitive()
+    }
+
+    /// Private method which returns stored spelling of `key`
+    fn key<'a>(&'a self, key: &'a str) -> &'a str {
+        self.aliases.resolve(&self.inner, key)
+    }
+
+    /// Get scalar value of key
+    ///
+    /// - output type `T` must implement [FromIniValue] trait for auto conversion
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\nkey=1\nvalue=2").unwrap();
+    ///
+    /// for (name, section) in conf.iter() {
+    ///     let key = section.get("key");
+    ///     let value = section.get("value");
+    ///     assert_eq!(key, Some(1));
+    ///     assert_eq!(value, Some(2));
+    /// }
+    /// ```
+    pub fn get<T>(&self, key: &str) -> Option<T>
+    where
+        T: FromIniValue,
+    {
+        self.get_raw(key).and_then(|x| T::from_ini_value(x).ok())
+    }
+
+    /// Fallible version of [`get()`](Section::get), see [Ini::try_get]
+    ///
+    /// # Errors
+    /// This function will return [GetError::Conversion] if value cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\nkey=1\nvalue=x").unwrap();
+    ///
+    /// for (name, section) in conf.iter() {
+    ///     assert_eq!(section.try_get::<u8>("key"), Ok(Some(1)));
+    ///     assert_eq!(section.try_get::<u8>("absent"), Ok(None));
+    ///     assert!(section.try_get::<u8>("value").is_err());
+    /// }
+    /// ```
+    pub fn try_get<T>(&self, key: &str) -> Result<Option<T>, GetError>
+    where
+        T: FromIniValue,
+    {
+        optional(self.require(key))
+    }
+
+    /// Get scalar value of mandatory key, see [Ini::require]
+    ///
+    /// # Errors
+    /// This function will return [GetError::MissingSectionKey] if key doesn't exist
+    /// and [GetError::Conversion] if value cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{GetError, Ini};
+    /// let conf = Ini::from_string("[section]\nkey=1").unwrap();
+    ///
+    /// for (name, section) in conf.iter() {
+    ///     assert_eq!(section.require::<u8>("key"), Ok(1));
+    ///     assert_eq!(section.require::<u8>("absent"), Err(GetError::MissingSectionKey("absent".to_owned())));
+    /// }
+    /// ```
+    pub fn require<T>(&self, key: &str) -> Result<T, GetError>
+    where
+        T: FromIniValue,
+    {
+        self.get_raw(key).ok_or_else(|| GetError::MissingSectionKey(key.to_owned())).and_then(|x| convert(x))
+    }
+
+    /// Get vector value of key, see [Ini::get_vec]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\nlist = 1, 2, 3").unwrap();
+    ///
+    /// for (_, section) in conf.iter() {
+    ///     assert_eq!(section.get_vec("list"), Some(vec![1, 2, 3]));
+    /// }
+    /// ```
+    pub fn get_vec<T>(&self, key: &str) -> Option<Vec<T>>
+    where
+        T: FromIniValue,
+    {
+        self.get_vec_with_sep(key, ",")
+    }
+
+    /// Get vector value of key separated by `sep`, see [Ini::get_vec_with_sep]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\npath = /bin:/usr/bin").unwrap();
+    ///
+    /// for (_, section) in conf.iter() {
+    ///     assert_eq!(section.get_vec_with_sep::<String>("path", ":").unwrap(), ["/bin", "/usr/bin"]);
+    /// }
+    /// ```
+    pub fn get_vec_with_sep<T>(&self, key: &str, sep: &str) -> Option<Vec<T>>
+    where
+        T: FromIniValue,
+    {
+        self.get_raw(key).and_then(|x| parse_list(x, sep).ok())
+    }
+
+    pub fn get_raw(&self, key: &str) -> Option<&String> {
+        self.inner.get(self.key(key))
+    }
+
+    /// Set `value` of `key`, or replace value if key already in section, see [Ini::set]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Section;
+    /// let mut section = Section::new();
+    /// section.set("port", 8080);
+    /// section.set("debug", true);
+    ///
+    /// assert_eq!(section.get_raw("port"), Some(&"8080".to_owned()));
+    /// assert_eq!(section.get("debug"), Some(true));
+    /// ```
+    pub fn set<V>(&mut self, key: &str, value: V)
+    where
+        V: ToIniValue,
+    {
+        self.insert(key.to_owned(), value.to_ini_value());
+    }
+
+    /// Set vector value of `key` separated by `sep`, see [Ini::item_vec_with_sep]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Section;
+    /// let mut section = Section::new();
+    /// section.set_vec_with_sep("path", &["/bin", "/usr/bin"], ":");
+    ///
+    /// assert_eq!(section.get_raw("path"), Some(&"/bin:/usr/bin".to_owned()));
+    /// ```
+    pub fn set_vec_with_sep<V>(&mut self, key: &str, vector: &[V], sep: &str)
+    where
+        V: ToIniValue,
+    {
+        self.insert(key.to_owned(), join_list(vector, sep));
+    }
+
+    /// Equivalent of [`set_vec_with_sep(key, vector, ", ")`](Section::set_vec_with_sep)
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Section;
+    /// let mut section = Section::new();
+    /// section.set_vec("list", &[1, 2, 3]);
+    ///
+    /// assert_eq!(section.get_vec("list"), Some(vec![1, 2, 3]));
+    /// ```
+    pub fn set_vec<V>(&mut self, key: &str, vector: &[V])
+    where
+        V: ToIniValue,
+    {
+        self.set_vec_with_sep(key, vector, ", ")
+    }
+
+    /// Keep only key-value pairs for which `f` returns `true`, order of the rest is preserved
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[env]\nA = 1\nB =\nC = 3").unwrap();
+    ///
+    /// if let Some(section) = conf.section_mut("env") {
+    ///     section.retain(|_, value| !value.is_empty());
+    /// }
+    /// assert_eq!(conf.to_string(), "[env]\nA = 1\nC = 3\n");
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &mut String) -> bool,
+    {
+        let arrays = &mut self.arrays;
+        let comments = &mut self.comments;
+        self.inner.retain(|k, v| {
+            // php-style array stays array only if `f` doesn't change its value
+            let array = arrays.contains_key(k).then(|| v.clone());
+            let keep = f(k, v);
+            if !keep {
+                arrays.remove(k);
+                comments.remove(k);
+            } else if array.is_some_and(|old| old != *v) {
+                arrays.remove(k);
+            }
+            keep
+        });
+    }
+
+    /// Compare sections ignoring order of keys, see [Ini::eq_unordered]
+    pub fn eq_unordered(&self, other: &Section) -> bool {
+        self.inner.len() == other.inner.len()
+            && self.inner.iter().all(|(k, v)| {
+                let key = other.key(k);
+                other.inner.get(key) == Some(v) && self.arrays.get(k) == other.arrays.get(key)
+            })
+    }
+
+    /// Rewrite section in canonical form, see [Ini::canonicalize]
+    pub fn canonicalize(&mut self, order: SortOrder) {
+        self.sort_by(|a, _, b, _| order.compare(a, b));
+        for (key, value) in self.inner.iter_mut() {
+            *value = canonical::normalize(value, self.arrays.get(key.as_str()).copied());
+        }
+    }
+
+    /// Number of keys in section
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if section contains no keys
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// An iterator visiting all keys in order of appearance
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[section]\nb = 1\na = 2").unwrap();
+    ///
+    /// let keys: Vec<&String> = conf["section"].keys().collect();
+    /// assert_eq!(keys, ["b", "a"]);
+    /// ```
+    pub fn keys(&self) -> SectionKeys<'_> {
+        SectionKeys { iter: self.inner.keys() }
+    }
+
+    /// Rename key `old` to `new` keeping its position
+    ///
+    /// Returns `false` and leaves section unchanged if key `old` doesn't exist or key `new` already exists.
+    pub fn rename(&mut self, old: &str, new: &str) -> bool {
+        let old = match self.aliases.rename(&mut self.inner, old, new) {
+            Some(old) => old,
+            None => return false,
+        };
+        if let Some(kind) = self.arrays.remove(&old) {
+            self.arrays.insert(new.to_owned(), kind);
+        }
+        if let Some(comment) = self.comments.remove(&old) {
+            self.comments.insert(new.to_owned(), comment);
+        }
+        true
+    }
+
+    /// Insert `key` with `value` right before key `anchor`, existing `key` is replaced and moved
+    ///
+    /// Returns `false` and leaves section unchanged if key `anchor` doesn't exist or equals to `key`.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[server]\nhost = a\nport = 80").unwrap();
+    ///
+    /// assert!(conf["server"].insert_before("port", "proto", "tcp"));
+    /// assert!(conf["server"].insert_after("port", "host", "b"));
+    /// assert_eq!(conf.to_string(), "[server]\nproto = tcp\nport = 80\nhost = b\n");
+    /// ```
+    pub fn insert_before<V>(&mut self, anchor: &str, key: &str, value: V) -> bool
+    where
+        V: ToIniValue,
+    {
+        let key = self.aliases.insert(&self.inner, key.to_owned());
+        self.arrays.remove(&key);
+        self.inner.insert_before(self.aliases.resolve(&self.inner, anchor), key, value.to_ini_value())
+    }
+
+    /// Insert `key` with `value` right after key `anchor`, see [`insert_before()`](Section::insert_before)
+    pub fn insert_after<V>(&mut self, anchor: &str, key: &str, value: V) -> bool
+    where
+        V: ToIniValue,
+    {
+        let key = self.aliases.insert(&self.inner, key.to_owned());
+        self.arrays.remove(&key);
+        self.inner.insert_after(self.aliases.resolve(&self.inner, anchor), key, value.to_ini_value())
+    }
+
+    /// Sort keys with comparator function `cmp`, which receives two key-value pairs. The sort is stable.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let mut conf = Ini::from_string("[env]\nB = 1\nA = 2").unwrap();
+    ///
+    /// conf["env"].sort_by(|k1, _, k2, _| k1.cmp(k2));
+    /// assert_eq!(conf.to_string(), "[env]\nA = 2\nB = 1\n");
+    /// ```
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&str, &str, &str, &str) -> Ordering,
+    {
+        self.inner.sort_by(|k1, v1, k2, v2| cmp(k1, v1, k2, v2));
+    }
+
+    /// Get value of `key` translated to `locale` (e.g. `Name[de_DE]`).
+    ///
+    /// Keys are looked up in order `key[lang_COUNTRY@MODIFIER]`, `key[lang_COUNTRY]`, `key[lang@MODIFIER]`,
+    /// `key[lang]` and `key`. Encoding part of `locale` (e.g. `.UTF-8`) is ignored.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[Desktop Entry]\nName = Editor\nName[de] = Bearbeiter").unwrap();
+    ///
+    /// for (_, section) in conf.iter() {
+    ///     assert_eq!(section.get_localized("Name", "de_DE.UTF-8"), Some("Bearbeiter".to_owned()));
+    ///     assert_eq!(section.get_localized("Name", "fr_FR"), Some("Editor".to_owned()));
     /// }
+    /// ```
+    pub fn get_localized<T>(&self, key: &str, locale: &str) -> Option<T>
+    where
+        T: FromIniValue,
+    {
+        self.get_localized_raw(key, locale).and_then(|x| T::from_ini_value(x).ok())
+    }
+
+    /// Like [`get_localized()`](Section::get_localized), but returns raw value
+    pub fn get_localized_raw(&self, key: &str, locale: &str) -> Option<&String> {
+        locale::fallbacks(locale)
+            .iter()
+            .find_map(|locale| self.get_raw(&format!("{}[{}]", key, locale)))
+            .or_else(|| self.get_raw(key))
+    }
+
+    /// Iterate over all translations of `key` in order of appearance, yielding pairs of locale and value.
+    /// Value of `key` without locale is not included.
     ///
-    /// for (name, section) in conf.iter() {
-    ///     for (key, val) in section.iter() {
-    ///         assert_eq!(val.as_str(), "replaced");
-    ///     }
+    /// # Example
+    /// ```
+    /// # use tini::Ini;
+    /// let conf = Ini::from_string("[app]\nName = Editor\nName[de] = Bearbeiter\nName[fr] = Éditeur").unwrap();
+    ///
+    /// for (_, section) in conf.iter() {
+    ///     let locales: Vec<&str> = section.translations("Name").map(|(locale, _)| locale).collect();
+    ///     assert_eq!(locales, ["de", "fr"]);
     /// }
-    pub fn iter_mut(&mut self) -> IniIterMut {
-        IniIterMut { iter: self.document.iter_mut() }
+    /// ```
+    pub fn translations<'a>(&'a self, key: &'a str) -> Translations<'a> {
+        Translations { iter: self.iter(), key, insensitive: self.is_case_insensitive() }
     }
-}
 
-impl fmt::Display for Ini {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut items = Vec::new();
-        for (name, section) in self.iter() {
-            // insert section block
-            items.push(format!("[{}]", name));
-            // add items
-            for (key, value) in section.iter() {
-                items.push(format!("{} = {}", key, value));
+    /// Set value of `key` translated to `locale`, i.e. value of `key[locale]`
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Section;
+    /// let mut section = Section::new();
+    /// section.set_localized("Name", "de", "Bearbeiter");
+    ///
+    /// assert_eq!(section.get_raw("Name[de]"), Some(&"Bearbeiter".to_owned()));
+    /// ```
+    pub fn set_localized<V>(&mut self, key: &str, locale: &str, value: V)
+    where
+        V: ToIniValue,
+    {
+        self.insert(format!("{}[{}]", key, locale), value.to_ini_value());
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let key = self.aliases.resolve(&self.inner, key);
+        self.arrays.remove(key);
+        self.comments.remove(key);
+        self.inner.remove(key)
+    }
+
+    pub fn insert(&mut self, key: String, value: String) {
+        let key = self.aliases.insert(&self.inner, key);
+        invalidate(&mut self.arrays, &key);
+        self.inner.insert(key, value);
+    }
+
+    /// Private method which switches case folding of keys to the mode of document,
+    /// keys which differ only by case are merged like duplicates
+    fn with_case(self, insensitive: bool) -> Section {
+        if self.is_case_insensitive() == insensitive {
+            return self;
+        }
+        let mut section = Section::with_aliases(Aliases::new(insensitive));
+        section.merge(self);
+        section
+    }
+
+    /// Private method which appends keys of `other` and replaces values of existing ones
+    fn merge(&mut self, other: Section) {
+        for (key, value) in other.inner {
+            self.insert(key.clone(), value);
+            let stored = self.key(&key).to_owned();
+            if let Some(kind) = other.arrays.get(&key) {
+                self.arrays.insert(stored.clone(), *kind);
+            }
+            if let Some(comment) = other.comments.get(&key) {
+                self.comments.insert(stored, comment.clone());
             }
-            // and blank line between sections
-            items.push("".to_string());
         }
-        write!(f, "{}", items.join("\n"))
+        if other.comment.is_some() {
+            self.comment = other.comment;
+        }
     }
-}
 
-impl Default for Ini {
-    fn default() -> Self {
-        Self::new()
+    /// Set comment above section header, empty `text` removes it
+    pub fn set_comment(&mut self, text: &str) {
+        self.comment = Some(text.to_owned()).filter(|text| !text.is_empty());
     }
-}
 
-/// An iterator over the sections of an ini document
-pub struct IniIter<'a> {
-    #[doc(hidden)]
-    iter: ordered_hashmap::Iter<'a, String, Section>,
-}
+    /// Get comment block directly above section header
+    pub fn get_comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
 
-impl<'a> Iterator for IniIter<'a> {
-    type Item = (&'a String, &'a Section);
+    /// Set comment above `key`, empty `text` removes it
+    ///
+    /// Returns `false` and leaves section unchanged if key doesn't exist.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Section;
+    /// let mut section = Section::new();
+    /// section.set("port", 80);
+    ///
+    /// assert!(section.comment_key("port", "listening port"));
+    /// assert!(!section.comment_key("host", "address"));
+    /// assert_eq!(section.get_key_comment("port"), Some("listening port"));
+    /// ```
+    pub fn comment_key(&mut self, key: &str, text: &str) -> bool {
+        let key = self.key(key).to_owned();
+        if !self.inner.contains_key(&key) {
+            return false;
+        }
+        match text.is_empty() {
+            true => self.comments.remove(&key),
+            false => self.comments.insert(key, text.to_owned()),
+        };
+        true
+    }
 
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+    /// Get comment block directly above `key`
+    pub fn get_key_comment(&self, key: &str) -> Option<&str> {
+        self.comments.get(self.key(key)).map(String::as_str)
     }
-}
 
-/// A mutable iterator over the sections of an ini document
-pub struct IniIterMut<'a> {
-    #[doc(hidden)]
-    iter: ordered_hashmap::IterMut<'a, String, Section>,
-}
+    pub fn iter(&self) -> SectionIter<'_> {
+        SectionIter { iter: self.inner.iter() }
+    }
 
-impl<'a> Iterator for IniIterMut<'a> {
-    type Item = (&'a String, &'a mut Section);
+    /// Mutable iterator over key-value pairs, keys which are visited are no longer
+    /// written as [php-style arrays](crate::ParseOptions::php_arrays)
+    pub fn iter_mut(&mut self) -> SectionIterMut<'_> {
+        SectionIterMut { iter: self.inner.iter_mut(), arrays: &mut self.arrays }
+    }
 
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+    /// Get entry of `key` for in-place manipulation, see [Ini::entry]
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::Section;
+    /// let mut section = Section::new();
+    /// section.entry("retries").or_insert(3);
+    /// section.entry("retries").or_insert(5);
+    ///
+    /// assert_eq!(section.get("retries"), Some(3));
+    /// ```
+    pub fn entry(&mut self, key: &str) -> Entry<'_> {
+        let key = self.aliases.insert(&self.inner, key.to_owned());
+        let inner = match self.inner.entry(key) {
+            map::Entry::Occupied(entry) => EntryInner::Occupied(entry, &mut self.arrays),
+            map::Entry::Vacant(entry) => EntryInner::VacantKey(entry),
+        };
+        Entry { inner }
     }
 }
 
-#[derive(Debug)]
-pub struct Section {
-    inner: OrderedHashMap<String, String>,
+impl Default for Section {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-pub struct SectionIter<'a> {
-    #[doc(hidden)]
-    iter: ordered_hashmap::Iter<'a, String, String>,
+impl PartialEq for Section {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner && self.arrays == other.arrays
+    }
 }
 
-impl<'a> Iterator for SectionIter<'a> {
-    type Item = (&'a String, &'a String);
+impl Eq for Section {}
 
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
-    }
-}
+impl IntoIterator for Section {
+    type Item = (String, String);
+    type IntoIter = SectionIntoIter;
 
-pub struct SectionIterMut<'a> {
-    #[doc(hidden)]
-    iter: ordered_hashmap::IterMut<'a, String, String>,
+    fn into_iter(self) -> Self::IntoIter {
+        SectionIntoIter { iter: self.inner.into_iter() }
+    }
 }
 
-impl<'a> Iterator for SectionIterMut<'a> {
-    type Item = (&'a String, &'a mut String);
+impl<'a> IntoIterator for &'a Section {
+    type Item = (&'a String, &'a String);
+    type IntoIter = SectionIter<'a>;
 
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next()
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
-impl Section {
-    pub fn new() -> Self {
-        Section { inner: OrderedHashMap::new() }
+/// Collect key-value pairs into section, see [Extend] implementation
+impl<K, V> FromIterator<(K, V)> for Section
+where
+    K: Into<String>,
+    V: ToIniValue,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut section = Section::new();
+        section.extend(iter);
+        section
     }
+}
 
-    /// Get scalar value of key
-    ///
-    /// - output type `T` must implement [FromStr] trait for auto conversion
-    ///
-    /// # Example
-    /// ```
-    /// # use tini::Ini;
-    /// let conf = Ini::from_string("[section]\nkey=1\nvalue=2").unwrap();
-    ///
-    /// for (name, section) in conf.iter() {
-    ///     let key = section.get("key");
-    ///     let value = section.get("value");
-    ///     assert_eq!(key, Some(1));
-    ///     assert_eq!(value, Some(2));
-    /// }
-    /// ```
-    pub fn get<'a, T>(&'a self, key: &str) -> Option<T>
+/// Set key-value pairs in order of iteration, replacing values of existing keys
+///
+/// # Example
+/// ```
+/// # use tini::Section;
+/// let mut section = Section::new();
+/// section.extend(vec![("a", 1), ("b", 2)]);
+///
+/// assert_eq!(section.keys().collect::<Vec<_>>(), ["a", "b"]);
+/// ```
+impl<K, V> Extend<(K, V)> for Section
+where
+    K: Into<String>,
+    V: ToIniValue,
+{
+    fn extend<I>(&mut self, iter: I)
     where
-        T: FromStr,
+        I: IntoIterator<Item = (K, V)>,
     {
-        self.inner.get(key).and_then(|x| x.parse().ok())
+        for (k, v) in iter {
+            self.insert(k.into(), v.to_ini_value());
+        }
     }
+}
 
-    pub fn get_raw<'a>(&'a self, key: &str) -> Option<&String> {
-        self.inner.get(key)
+/// Access raw value by key
+///
+/// Output is `String` rather than `str`, because [`IndexMut`] shares it and a value
+/// is replaced by assignment like `ini["section"]["key"] = "value".to_owned()`.
+/// It derefs to `&str` wherever one is expected.
+///
+/// # Panics
+/// Panics if key doesn't exist, use [`get_raw()`](Section::get_raw) to avoid it
+impl Index<&str> for Section {
+    type Output = String;
+
+    fn index(&self, key: &str) -> &String {
+        match self.get_raw(key) {
+            Some(value) => value,
+            None => panic!("Key `{}` not found", key),
+        }
     }
+}
 
-    pub fn remove(&mut self, key: &str) -> Option<String> {
-        self.inner.remove(key)
+/// Access raw value by key mutably
+///
+/// # Panics
+/// Panics if key doesn't exist, use [`entry()`](Section::entry) to insert missing keys
+impl IndexMut<&str> for Section {
+    fn index_mut(&mut self, key: &str) -> &mut String {
+        let key_name = self.aliases.resolve(&self.inner, key);
+        invalidate(&mut self.arrays, key_name);
+        match self.inner.get_mut(key_name) {
+            Some(value) => value,
+            None => panic!("Key `{}` not found", key),
+        }
     }
+}
 
-    pub fn insert(&mut self, key: String, value: String) {
-        self.inner.insert(key, value);
+/// Forget that `key` was parsed from php-style array, it's called whenever its value may change
+fn invalidate(arrays: &mut HashMap<String, ArrayKind>, key: &str) {
+    if !arrays.is_empty() {
+        arrays.remove(key);
     }
+}
 
-    pub fn iter(&self) -> SectionIter {
-        SectionIter { iter: self.inner.iter() }
+/// Split value of php-style array key into bracket indexes and values, [None] if value is malformed
+fn array_items(kind: ArrayKind, value: &str) -> Option<Vec<(String, String)>> {
+    let elements = list::split(value, ",").ok()?;
+    match kind {
+        ArrayKind::List => Some(elements.into_iter().map(|v| (String::new(), v)).collect()),
+        ArrayKind::Map => elements.iter().map(|entry| list::split_pair(entry, ":").ok()).collect(),
+    }
+}
+
+/// Convert raw value to `T`, keeping information about the failure
+fn convert<T>(value: &str) -> Result<T, GetError>
+where
+    T: FromIniValue,
+{
+    T::from_ini_value(value).map_err(|e| GetError::Conversion {
+        value: value.to_owned(),
+        type_name: type_name::<T>(),
+        message: e.to_string(),
+    })
+}
+
+/// Convert raw value separated by `sep` to vector of `T`
+fn convert_vec<T>(value: &str, sep: &str) -> Result<Vec<T>, GetError>
+where
+    T: FromIniValue,
+{
+    let elements = list::split(value, sep).map_err(|e| GetError::Conversion {
+        value: value.to_owned(),
+        type_name: type_name::<Vec<T>>(),
+        message: e.to_string(),
+    })?;
+    elements.iter().map(|s| convert(s)).collect()
+}
+
+/// Convert raw range list value separated by `sep` to vector of `T`
+fn convert_ranges<T>(value: &str, sep: &str) -> Result<Vec<T>, GetError>
+where
+    T: RangeInteger,
+{
+    let elements = list::split(value, sep).map_err(|e| GetError::Conversion {
+        value: value.to_owned(),
+        type_name: type_name::<Vec<T>>(),
+        message: e.to_string(),
+    })?;
+    let mut result = Vec::new();
+    for element in elements {
+        range::expand(&element, &mut result).map_err(|e| GetError::Conversion {
+            value: element.clone(),
+            type_name: type_name::<T>(),
+            message: e.to_string(),
+        })?;
     }
+    Ok(result)
+}
+
+/// Convert raw inline map value to collection of `(K, V)` pairs
+fn convert_map<K, V, M>(value: &str, pair_sep: &str, entry_sep: &str) -> Result<M, GetError>
+where
+    K: FromIniValue,
+    V: FromIniValue,
+    M: FromIterator<(K, V)>,
+{
+    let entries = list::split(value, entry_sep).map_err(|e| GetError::Conversion {
+        value: value.to_owned(),
+        type_name: type_name::<M>(),
+        message: e.to_string(),
+    })?;
+    entries
+        .iter()
+        .map(|entry| {
+            let (k, v) = list::split_pair(entry, pair_sep).map_err(|e| GetError::Conversion {
+                value: entry.clone(),
+                type_name: type_name::<(K, V)>(),
+                message: e.to_string(),
+            })?;
+            let with_entry = |e: GetError| match e {
+                GetError::Conversion { value, type_name, message } => GetError::Conversion {
+                    value: entry.clone(),
+                    type_name,
+                    message: format!("`{}`: {}", value, message),
+                },
+                e => e,
+            };
+            Ok((convert(&k).map_err(with_entry)?, convert(&v).map_err(with_entry)?))
+        })
+        .collect()
+}
 
-    pub fn iter_mut(&mut self) -> SectionIterMut {
-        SectionIterMut { iter: self.inner.iter_mut() }
+/// Treat missing key as absent value, missing section is still an error
+fn optional<T>(result: Result<T, GetError>) -> Result<Option<T>, GetError> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(GetError::MissingKey { .. }) | Err(GetError::MissingSectionKey(_)) => Ok(None),
+        Err(e) => Err(e),
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod library_test {
     use super::*;
 
@@ -736,12 +2978,241 @@ mod library_test {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn bad_cast() {
         let ini = Ini::new().section("one").item("a", 3.14);
         let a: Option<u32> = ini.get("one", "a");
         assert_eq!(a, None);
     }
 
+    #[test]
+    fn try_get_conversion_error() -> Result<(), Error> {
+        let ini = Ini::from_string("[server]\nport = eighty")?;
+        match ini.try_get::<u16>("server", "port") {
+            Err(GetError::Conversion { value, type_name, message }) => {
+                assert_eq!(value, "eighty");
+                assert_eq!(type_name, "u16");
+                assert_eq!(message, "invalid digit found in string");
+            }
+            _ => unreachable!(),
+        }
+        assert_eq!(ini.try_get::<u16>("server", "absent"), Ok(None));
+        assert_eq!(ini.try_get::<u16>("absent", "port"), Err(GetError::MissingSection("absent".to_owned())));
+        Ok(())
+    }
+
+    #[test]
+    fn require_missing() -> Result<(), Error> {
+        let ini = Ini::from_string("[server]\nports = 80, x")?;
+        assert_eq!(ini.require::<u16>("client", "port"), Err(GetError::MissingSection("client".to_owned())));
+        assert_eq!(
+            ini.require::<u16>("server", "port"),
+            Err(GetError::MissingKey { section: "server".to_owned(), key: "port".to_owned() })
+        );
+        match ini.require_vec::<u16>("server", "ports") {
+            Err(GetError::Conversion { value, .. }) => assert_eq!(value, "x"),
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn typed_values() -> Result<(), Error> {
+        let ini = Ini::from_string("[a]\nflag = yes\nlimit =\npath = /tmp/x\nletter = z\npair = 1, 2")?;
+        assert_eq!(ini.get("a", "flag"), Some(true));
+        assert_eq!(ini.get("a", "limit"), Some(None::<u32>));
+        assert_eq!(ini.get("a", "path"), Some(std::path::PathBuf::from("/tmp/x")));
+        assert_eq!(ini.get("a", "letter"), Some('z'));
+        assert_eq!(ini.get("a", "pair"), Some((1, 2)));
+        assert_eq!(ini.get("a", "pair"), Some(vec![1, 2]));
+        Ok(())
+    }
+
+    #[test]
+    fn radix_integers() -> Result<(), Error> {
+        let ini = Ini::from_string("[hw]\nmask = 0xFF00\nmode = 0o755\nbits = 0b1010, 0x10, 1_000")?;
+        assert_eq!(ini.get("hw", "mask"), Some(0xFF00u32));
+        assert_eq!(ini.get("hw", "mode"), Some(0o755u16));
+        assert_eq!(ini.get_vec("hw", "bits"), Some(vec![10u32, 16, 1000]));
+        Ok(())
+    }
+
+    #[test]
+    fn inline_map() -> Result<(), Error> {
+        let ini = Ini::from_string("[a]\nweights = cpu:2, io:1\nbad = cpu:2, io\nempty =")?;
+        let weights: Vec<(String, u8)> = ini.get_map("a", "weights").unwrap();
+        assert_eq!(weights, [("cpu".to_owned(), 2), ("io".to_owned(), 1)]);
+        match ini.try_get_map::<String, u8, Vec<_>>("a", "bad") {
+            Err(GetError::Conversion { value, .. }) => assert_eq!(value, "io"),
+            _ => unreachable!(),
+        }
+        assert_eq!(ini.get_map::<String, u8, Vec<_>>("a", "empty"), Some(vec![]));
+        let ini = Ini::new().section("a").item_map("m", weights.iter().map(|(k, v)| (k, v)));
+        assert_eq!(ini.get_map::<String, u8, Vec<_>>("a", "m"), Some(weights));
+
+        let entries = vec![("a:b".to_owned(), "x,y".to_owned()), ("url".to_owned(), "http://h:80; #1".to_owned())];
+        let text = Ini::new().section("a").item_map("m", entries.clone()).to_string();
+        let ini = Ini::from_string(text)?;
+        assert_eq!(ini.get_map::<String, String, Vec<_>>("a", "m"), Some(entries));
+        Ok(())
+    }
+
+    #[test]
+    fn php_arrays() -> Result<(), Error> {
+        let text = "[php]\nextension[] = gd\nmode = 1\nextension[] = curl\nopts[debug] = on\n";
+        let ini = Ini::from_string(text)?;
+        assert_eq!(ini.get::<String>("php", "extension[]"), Some("curl".to_owned()));
+
+        let ini = Ini::from_string_with(text, &ParseOptions::new().php_arrays(true))?;
+        assert_eq!(ini.get_vec::<String>("php", "extension").unwrap(), ["gd", "curl"]);
+        let opts: HashMap<String, bool> = ini.get_map("php", "opts").unwrap();
+        assert_eq!(opts.get("debug"), Some(&true));
+        assert_eq!(ini.to_string(), "[php]\nextension[] = gd\nextension[] = curl\nmode = 1\nopts[debug] = on\n");
+        Ok(())
+    }
+
+    #[test]
+    fn php_arrays_mutation() -> Result<(), Error> {
+        let text = "[php]\na[] = 1\nb[] = 2\nc[] = 3\nd[] = 4\ne[] = 5\nf[] = 6\nmap[x:y] = 1\n";
+        let mut ini = Ini::from_string_with(text, &ParseOptions::new().php_arrays(true))?;
+        ini.entry("php", "a").and_modify(|v| v.push('0'));
+        ini.entry("php", "b").and_modify_as(|v: u8| v + 1).unwrap();
+        ini["php"]["c"] = "x".to_owned();
+        ini.set("php", "d", 7);
+        ini["php"].retain(|k, v| {
+            if k == "e" {
+                *v = "50".to_owned();
+            }
+            true
+        });
+        assert_eq!(ini.get_map::<String, u8, Vec<_>>("php", "map"), Some(vec![("x:y".to_owned(), 1)]));
+        assert_eq!(ini.to_string(), "[php]\na = 10\nb = 3\nc = x\nd = 7\ne = 50\nf[] = 6\nmap[x:y] = 1\n");
+        for (_, value) in ini["php"].iter_mut() {
+            value.push('!');
+        }
+        assert!(ini.to_string().contains("\nf = 6!\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn in_place_editing() -> Result<(), Error> {
+        let mut ini = Ini::from_string("[a]\nx = 1\n[b]\ny = 2")?;
+        ini.set("a", "x", 10);
+        ini.set("c", "z", true);
+        ini.set_vec("b", "list", &["p", "q"]);
+        assert_eq!(ini.remove_key("b", "y"), Some("2".to_owned()));
+        assert_eq!(ini.remove_key("missing", "y"), None);
+        assert!(ini.remove_section("missing").is_none());
+        assert_eq!(ini.to_string(), "[a]\nx = 10\n\n[b]\nlist = p, q\n\n[c]\nz = true\n");
+        assert!(ini.remove_section("b").is_some());
+        assert_eq!(ini.to_string(), "[a]\nx = 10\n\n[c]\nz = true\n");
+        Ok(())
+    }
+
+    #[test]
+    fn entry() -> Result<(), Error> {
+        let mut ini = Ini::from_string("[a]\nx = 1\ny = text")?;
+        // entries which weren't filled don't create keys or sections
+        assert_eq!(ini.entry("b", "z").get_raw(), None);
+        assert_eq!(ini.entry("a", "z").key(), "z");
+        ini.entry("a", "x").and_modify(|v| v.push('0'));
+        let error = ini.entry("a", "y").and_modify_as(|v: u8| v + 1).err();
+        assert!(matches!(error, Some(GetError::Conversion { .. })));
+        *ini.entry("b", "z").or_insert(1) += "2";
+        assert_eq!(ini.to_string(), "[a]\nx = 10\ny = text\n\n[b]\nz = 12\n");
+        Ok(())
+    }
+
+    #[test]
+    fn index() -> Result<(), Error> {
+        let mut ini = Ini::from_string("[a]\nx = 1")?;
+        assert_eq!(ini["a"]["x"], "1");
+        ini["a"]["x"].push('0');
+        assert_eq!(ini.get("a", "x"), Some(10));
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Section `b` not found")]
+    fn index_missing_section() {
+        let ini = Ini::new();
+        let _ = &ini["b"];
+    }
+
+    #[test]
+    #[should_panic(expected = "Key `y` not found")]
+    fn index_missing_key() {
+        let ini = Ini::from_string("[a]\nx = 1").unwrap();
+        let _ = &ini["a"]["y"];
+    }
+
+    #[test]
+    fn section_bulk() {
+        let mut section = Section::new();
+        assert!(section.is_empty());
+        section.extend((0..6).map(|i| (format!("k{}", i), i)));
+        section.set("k1", "one");
+        section.retain(|k, _| k != "k0" && k != "k3");
+        assert_eq!(section.len(), 4);
+        let pairs: Vec<_> = section.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        assert_eq!(pairs, ["k1=one", "k2=2", "k4=4", "k5=5"]);
+    }
+
+    #[test]
+    fn reorder() -> Result<(), Error> {
+        let options = ParseOptions::new().php_arrays(true);
+        let mut ini = Ini::from_string_with("[a]\nx = 1\nlist[] = 1\nlist[] = 2\n[b]\ny = 2", &options)?;
+        assert!(ini.move_key("a", "list", "c"));
+        assert!(!ini.move_key("a", "list", "c"));
+        assert!(ini.move_key("a", "x", "a"));
+        assert!(ini.rename_key("c", "list", "items"));
+        assert!(!ini.rename_key("missing", "x", "z"));
+        assert!(ini.rename_section("b", "d"));
+        ini.sort_sections_by(|n1, _, n2, _| n2.cmp(n1));
+        assert_eq!(ini.to_string(), "[d]\ny = 2\n\n[c]\nitems[] = 1\nitems[] = 2\n\n[a]\nx = 1\n");
+        Ok(())
+    }
+
+    #[test]
+    fn case_insensitive() -> Result<(), Error> {
+        let text = "[Server]\nPort = 80\nHost = a\n[other]\nx = 1\n[SERVER]\nPORT = 8080";
+        let mut ini = Ini::from_string_with(text, &ParseOptions::new().case_insensitive(true))?;
+        assert!(ini.is_case_insensitive());
+        assert_eq!(ini.get("server", "port"), Some(8080));
+        assert_eq!(ini["SERVER"]["HOST"], "a");
+        ini.set("server", "HOST", "b");
+        ini.set("OTHER", "Y", 2);
+        assert!(ini.rename_key("server", "host", "Address"));
+        assert!(!ini.rename_key("server", "address", "PORT"));
+        assert!(ini.rename_section("other", "Other"));
+        assert_eq!(ini.remove_key("other", "X"), Some("1".to_owned()));
+        *ini.entry("server", "TIMEOUT").or_insert(5) += "0";
+        assert_eq!(ini.to_string(), "[Server]\nPort = 8080\nAddress = b\nTIMEOUT = 50\n\n[Other]\nY = 2\n");
+        // sections join case-insensitive document in its mode
+        let mut plain = Section::new();
+        plain.set("Key", 1);
+        plain.set("KEY", 2);
+        assert!(ini.insert_section_after("server", "Added", plain.clone()));
+        assert!(ini.insert_section_before("SERVER", "First", plain.clone()));
+        ini.extend([("Extended".to_owned(), plain.clone())]);
+        for name in ["added", "FIRST", "extended"] {
+            assert!(ini[name].is_case_insensitive());
+            assert_eq!(ini[name].keys().collect::<Vec<_>>(), ["Key"]);
+            assert_eq!(ini.get(name, "key"), Some(2));
+        }
+        let collected: Ini = [("s".to_owned(), plain.clone())].into_iter().collect();
+        assert!(!collected["s"].is_case_insensitive());
+        let mut upper = Section::new_case_insensitive();
+        upper.set("KEY", 2);
+        assert!(ini["added"].eq_unordered(&upper));
+        assert!(upper.eq_unordered(&ini["added"]));
+        // default mode is case-sensitive
+        let ini = Ini::from_string(text)?;
+        assert_eq!(ini.get::<u16>("server", "port"), None);
+        assert_eq!(ini.get("SERVER", "PORT"), Some(8080));
+        Ok(())
+    }
+
     #[test]
     fn string_vec() -> Result<(), Error> {
         let ini = Ini::from_string("[section]\nname=a, b, c")?;
@@ -750,6 +3221,22 @@ mod library_test {
         Ok(())
     }
 
+    #[test]
+    fn list_round_trip() -> Result<(), Error> {
+        let elements = ["a;b", "#tag", "x, y", "\"q\" ; #", "two\nlines", "cr\r\nlf", "plain"];
+        let text = Ini::new().section("a").item_vec("list", &elements).item("after", 1).to_string();
+        let ini = Ini::from_string(text.as_str())?;
+        assert_eq!(ini.get_vec::<String>("a", "list").unwrap(), elements);
+        assert_eq!(ini.get("a", "after"), Some(1));
+        assert_eq!(IniRef::parse(&text)?.get_vec::<String>("a", "list").unwrap(), elements);
+        let lazy = LazyIni::new(io::Cursor::new(text.as_str()))?;
+        assert_eq!(lazy.get_vec::<String>("a", "list").unwrap(), elements);
+        let map = Ini::new().section("m").item_map("map", [("k\n", "v\r\n")]).to_string();
+        let map: HashMap<String, String> = Ini::from_string(map)?.get_map("m", "map").unwrap();
+        assert_eq!(map.get("k\n").map(String::as_str), Some("v\r\n"));
+        Ok(())
+    }
+
     #[test]
     fn parse_error() -> Result<(), Error> {
         let ini = Ini::from_string("[section]\nlist = 1, 2, --, 4")?;
@@ -797,4 +3284,89 @@ mod library_test {
         }
         Ok(())
     }
+
+    #[test]
+    fn std_traits() -> Result<(), Error> {
+        let options = ParseOptions::new().php_arrays(true);
+        let ini = Ini::from_string_with("[a]\nx = 1\nlist[] = 2\n[b]\ny = 3", &options)?;
+        let copy = ini.clone();
+        assert_eq!(ini, copy);
+        let mut sections: Vec<(String, Section)> = copy.clone().into_iter().collect();
+        sections.reverse();
+        let reordered: Ini = sections.into_iter().collect();
+        assert_ne!(ini, reordered);
+        assert!(ini.eq_unordered(&reordered));
+        // merging keeps php array kind
+        let mut merged = Ini::new();
+        merged.extend(copy.clone());
+        merged.extend(copy);
+        assert_eq!(merged, ini);
+        assert_eq!(merged.to_string(), ini.to_string());
+        let map = HashMap::<String, HashMap<String, String>>::from(ini.clone());
+        assert!(Ini::from(map).eq_unordered(&Ini::from_string("[a]\nx = 1\nlist = 2\n[b]\ny = 3")?));
+        let names: Vec<&String> = (&ini).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["a", "b"]);
+        let section: Section = vec![("k", 1), ("k", 2)].into_iter().collect();
+        assert_eq!(section.into_iter().collect::<Vec<_>>(), [("k".to_owned(), "2".to_owned())]);
+        Ok(())
+    }
+
+    #[test]
+    fn comments() -> Result<(), Error> {
+        let text = "# Sample\n#\n# config\n\n# first\n[a]\n# about x\nx = 1 ; inline\n\n# loose\n\ny = 2\n";
+        let mut ini = Ini::from_string(text)?;
+        assert_eq!(ini.get_header(), Some("Sample\n\nconfig"));
+        assert_eq!(ini.get_section_comment("a"), Some("first"));
+        assert_eq!(ini.get_key_comment("a", "x"), Some("about x"));
+        assert_eq!(ini.get_key_comment("a", "y"), Some("loose"));
+        let expected = "# Sample\n#\n# config\n\n# first\n[a]\n# about x\nx = 1\n# loose\ny = 2\n";
+        assert_eq!(ini.to_string(), expected);
+        assert_eq!(Ini::from_string(expected)?.to_string(), expected);
+        // comments follow keys
+        assert!(ini.rename_key("a", "x", "z"));
+        assert_eq!(ini.get_key_comment("a", "z"), Some("about x"));
+        assert!(ini.move_key("a", "z", "b"));
+        assert_eq!(ini.get_key_comment("b", "z"), Some("about x"));
+        ini.remove_key("b", "z");
+        ini.set("b", "z", 3);
+        assert_eq!(ini.get_key_comment("b", "z"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn comments_at_end() -> Result<(), Error> {
+        let ini = Ini::from_string("; only\n; comments")?;
+        assert_eq!(ini.get_header(), Some("only\ncomments"));
+        assert_eq!(ini.get_footer(), None);
+        assert_eq!(ini.to_string(), "; only\n; comments\n");
+        let ini = Ini::from_string("; header\n\n; rest\n\n")?;
+        assert_eq!(ini.get_header(), Some("header"));
+        assert_eq!(ini.get_footer(), Some("rest"));
+        assert_eq!(ini.to_string(), "; header\n\n; rest\n");
+        let text = "[a]\nx = 1\n; first\n\n; second\n";
+        let ini = Ini::from_string(text)?;
+        assert_eq!(ini.get_key_comment("a", "x"), None);
+        assert_eq!(ini.get_footer(), Some("first\n\nsecond"));
+        let expected = "[a]\nx = 1\n\n; first\n;\n; second\n";
+        assert_eq!(ini.to_string(), expected);
+        assert_eq!(Ini::from_string(expected)?.get_footer(), ini.get_footer());
+        // comment above the next section is not trailing
+        let ini = Ini::from_string("[a]\nx = 1\n; end\n\n; about b\n[b]\n")?;
+        assert_eq!(ini.get_footer(), None);
+        assert_eq!(ini.get_section_comment("b"), Some("end\n\nabout b"));
+        Ok(())
+    }
+
+    #[test]
+    fn loose_comments() -> Result<(), Error> {
+        let text = "[a]\nx = 1\n\n; between groups\n\ny = 2\n\n; first\n\n; second\n; about z\nz = 3\n";
+        let ini = Ini::from_string(text)?;
+        assert_eq!(ini.get_key_comment("a", "x"), None);
+        assert_eq!(ini.get_key_comment("a", "y"), Some("between groups"));
+        assert_eq!(ini.get_key_comment("a", "z"), Some("first\n\nsecond\nabout z"));
+        let expected = "[a]\nx = 1\n; between groups\ny = 2\n; first\n;\n; second\n; about z\nz = 3\n";
+        assert_eq!(ini.to_string(), expected);
+        assert_eq!(Ini::from_string(expected)?, ini);
+        Ok(())
+    }
 }
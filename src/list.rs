@@ -0,0 +1,337 @@
+//! List module
+//!
+//! Contains `split` and `join` routines, which read and write list values,
+//! and `join_map` and `split_pair` routines for inline maps (`a:1, b:2`).
+//!
+//! List grammar:
+//! * elements are separated by a separator string, whitespace around elements is ignored;
+//! * separator consisting only of whitespace (e.g. `" "`) splits on any run of whitespace;
+//! * element can be enclosed in double quotes to keep separators and surrounding whitespace,
+//!   inside quotes `\"` and `\\` are escapes for `"` and `\`, `\n` and `\r` are escapes for line breaks;
+//! * `;` and `#` start inline comment of the line only outside of quotes;
+//! * outside of quotes separator, `"` and `\` can be escaped with `\`;
+//! * empty value is an empty list, `""` is a list with one empty element.
+use crate::error::ValueError;
+
+/// Separator token as it is matched by reader: `None` means any run of whitespace
+fn token(sep: &str) -> Option<&str> {
+    let token = sep.trim();
+    if token.is_empty() {
+        None
+    } else {
+        Some(token)
+    }
+}
+
+/// Split list `value` into elements separated by `sep`
+pub fn split(value: &str, sep: &str) -> Result<Vec<String>, ValueError> {
+    let token = token(sep);
+    let mut result = Vec::new();
+    let mut rest = value.trim_start();
+    if rest.is_empty() {
+        return Ok(result);
+    }
+    loop {
+        let (element, tail) = match rest.strip_prefix('"') {
+            Some(quoted) => split_quoted(quoted)?,
+            None => split_unquoted(rest, token),
+        };
+        result.push(element);
+        let trimmed = tail.trim_start();
+        if trimmed.is_empty() {
+            return Ok(result);
+        }
+        rest = match token {
+            Some(token) => match trimmed.strip_prefix(token) {
+                Some(tail) => {
+                    let tail = tail.trim_start();
+                    if tail.is_empty() {
+                        // trailing separator means trailing empty element
+                        result.push(String::new());
+                        return Ok(result);
+                    }
+                    tail
+                }
+                None => return Err(ValueError::new(format!("expected `{}` before `{}`", token, trimmed))),
+            },
+            None if trimmed.len() < tail.len() => trimmed,
+            None => return Err(ValueError::new(format!("expected whitespace before `{}`", trimmed))),
+        };
+    }
+}
+
+/// Read quoted element until closing quote, return element and the rest of the string
+fn split_quoted(value: &str) -> Result<(String, &str), ValueError> {
+    let mut element = String::new();
+    let mut chars = value.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((element, &value[index + 1..])),
+            '\\' => match chars.clone().next() {
+                Some((_, escaped @ ('"' | '\\'))) => {
+                    element.push(escaped);
+                    chars.next();
+                }
+                Some((_, 'n')) => {
+                    element.push('\n');
+                    chars.next();
+                }
+                Some((_, 'r')) => {
+                    element.push('\r');
+                    chars.next();
+                }
+                _ => element.push(c),
+            },
+            _ => element.push(c),
+        }
+    }
+    Err(ValueError::new("unterminated quoted element"))
+}
+
+/// Read unquoted element until separator, return element and the rest of the string
+fn split_unquoted<'a>(value: &'a str, token: Option<&str>) -> (String, &'a str) {
+    let is_sep = |rest: &str| match token {
+        Some(token) => rest.starts_with(token),
+        None => rest.starts_with(char::is_whitespace),
+    };
+    let mut element = String::new();
+    // length of element which must be kept when trailing whitespace is removed
+    let mut keep = 0;
+    let mut index = 0;
+    while index < value.len() {
+        let rest = &value[index..];
+        if is_sep(rest) {
+            break;
+        }
+        if let Some(escaped) = rest.strip_prefix('\\') {
+            let length = if escaped.starts_with(['"', '\\']) {
+                1
+            } else if is_sep(escaped) {
+                match token {
+                    Some(token) => token.len(),
+                    None => escaped.chars().next().map_or(0, char::len_utf8),
+                }
+            } else {
+                0
+            };
+            if length > 0 {
+                element.push_str(&escaped[..length]);
+                keep = element.len();
+                index += 1 + length;
+                continue;
+            }
+        }
+        let c = rest.chars().next().unwrap_or_default();
+        element.push(c);
+        index += c.len_utf8();
+    }
+    let trimmed = element.trim_end().len().max(keep);
+    element.truncate(trimmed);
+    (element, &value[index..])
+}
+
+/// Check if element must be quoted to be read back by [split],
+/// `;` and `#` are quoted because they start inline comment outside of quotes,
+/// line breaks are quoted because they end the line
+fn needs_quotes(element: &str, token: Option<&str>) -> bool {
+    element.is_empty()
+        || element.trim() != element
+        || element.starts_with('"')
+        || element.contains('\\')
+        || element.contains([';', '#', '\n', '\r'])
+        || match token {
+            Some(token) => element.contains(token),
+            None => element.contains(char::is_whitespace),
+        }
+}
+
+/// Join list `elements` with `sep`, quoting elements when it is required to read them back
+pub fn join<I, S>(elements: I, sep: &str) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let token = token(sep);
+    let mut result = String::new();
+    for (index, element) in elements.into_iter().enumerate() {
+        let element = element.as_ref();
+        if index > 0 {
+            result.push_str(sep);
+        }
+        if needs_quotes(element, token) {
+            quote(element, &mut result);
+        } else {
+            result.push_str(element);
+        }
+    }
+    result
+}
+
+/// Write `element` in double quotes escaping `"`, `\` and line breaks
+fn quote(element: &str, result: &mut String) {
+    result.push('"');
+    for c in element.chars() {
+        match c {
+            '"' | '\\' => {
+                result.push('\\');
+                result.push(c);
+            }
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            _ => result.push(c),
+        }
+    }
+    result.push('"');
+}
+
+/// Join key-value pairs into inline map: entries are separated by `entry_sep` with list grammar,
+/// key and value are separated by `pair_sep`. Keys containing `pair_sep` and values which can't
+/// be read back as is are quoted.
+pub fn join_map<I, K, V>(entries: I, pair_sep: &str, entry_sep: &str) -> String
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let token = token(pair_sep);
+    let entries = entries.into_iter().map(|(k, v)| {
+        let (key, value) = (k.as_ref(), v.as_ref());
+        let mut entry = String::new();
+        match needs_quotes(key, token) {
+            true => quote(key, &mut entry),
+            false => entry.push_str(key),
+        }
+        entry.push_str(pair_sep);
+        // value is the rest of the entry, so separators in it don't need quotes
+        match value.starts_with('"') || value.contains(['\\', '\n', '\r']) || value.trim() != value {
+            true => quote(value, &mut entry),
+            false => entry.push_str(value),
+        }
+        entry
+    });
+    join(entries, entry_sep)
+}
+
+/// Split inline map `entry` into key and value, see [join_map]
+///
+/// Key ends at the first `pair_sep` outside of quotes, value is the rest of the entry.
+pub fn split_pair(entry: &str, pair_sep: &str) -> Result<(String, String), ValueError> {
+    let token = token(pair_sep);
+    let entry = entry.trim_start();
+    let (key, tail) = match entry.strip_prefix('"') {
+        Some(quoted) => split_quoted(quoted)?,
+        None => split_unquoted(entry, token),
+    };
+    let missing = || ValueError::new(format!("missing `{}` between key and value", pair_sep));
+    let value = match token {
+        Some(token) => tail.trim_start().strip_prefix(token).ok_or_else(missing)?,
+        None if tail.trim_start().len() < tail.len() => tail,
+        None => return Err(missing()),
+    };
+    let value = value.trim();
+    let value = match value.strip_prefix('"') {
+        Some(quoted) => match split_quoted(quoted)? {
+            (value, rest) if rest.trim().is_empty() => value,
+            (_, rest) => return Err(ValueError::new(format!("unexpected `{}` after quoted value", rest.trim()))),
+        },
+        None => unescape(value),
+    };
+    Ok((key, value))
+}
+
+/// Replace `\"` and `\\` escapes in unquoted value, other backslashes are kept
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(&escaped @ ('"' | '\\'))) => {
+                result.push(escaped);
+                chars.next();
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parts(value: &str, sep: &str) -> Vec<String> {
+        split(value, sep).unwrap()
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(parts("a, b ,c", ","), ["a", "b", "c"]);
+        assert_eq!(parts("a|b|c", "|"), ["a", "b", "c"]);
+        assert_eq!(parts("a, , b,", ", "), ["a", "", "b", ""]);
+        assert_eq!(parts("hello world", ","), ["hello world"]);
+    }
+
+    #[test]
+    fn empty() {
+        assert!(parts("", ",").is_empty());
+        assert!(parts("   ", ",").is_empty());
+        assert_eq!(parts("\"\"", ","), [""]);
+    }
+
+    #[test]
+    fn quoted() {
+        assert_eq!(parts("\"a, b\", c", ","), ["a, b", "c"]);
+        assert_eq!(parts("\" x \", \"say \\\"hi\\\"\"", ","), [" x ", "say \"hi\""]);
+        assert_eq!(parts("\"C:\\\\dir\", C:\\dir", ","), ["C:\\dir", "C:\\dir"]);
+        assert!(split("\"a, b", ",").is_err());
+        assert!(split("\"a\" b, c", ",").is_err());
+        assert_eq!(parts("\"a\\nb\\r\", a\\nb", ","), ["a\nb\r", "a\\nb"]);
+    }
+
+    #[test]
+    fn escaped() {
+        assert_eq!(parts("a\\, b, c", ","), ["a, b", "c"]);
+        assert_eq!(parts("a\\\\, b", ","), ["a\\", "b"]);
+        assert_eq!(parts("a\\::b::c", "::"), ["a::b", "c"]);
+    }
+
+    #[test]
+    fn whitespace() {
+        assert_eq!(parts("  a b\t\tc ", " "), ["a", "b", "c"]);
+        assert_eq!(parts("\"a b\" c\\ d", " "), ["a b", "c d"]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let elements = ["", " x ", "a, b", "\"q\"", "back\\slash", "a::b", "a b", "trail\\", "plain", "two\nlines\r\n"];
+        for sep in [",", ", ", "|", "::", " ", "\t"] {
+            let joined = join(elements.iter(), sep);
+            assert_eq!(parts(&joined, sep), elements, "separator `{}`", sep);
+        }
+        assert_eq!(parts(&join(Vec::<String>::new(), ","), ","), Vec::<String>::new());
+        assert_eq!(parts(&join([""], ","), ","), [""]);
+    }
+
+    #[test]
+    fn map() {
+        let joined = join_map([("b", "x, y"), ("a", "1")], ":", ", ");
+        assert_eq!(joined, "\"b:x, y\", a:1");
+        assert_eq!(parts(&joined, ","), ["b:x, y", "a:1"]);
+        assert!(split_pair("b", ":").is_err());
+    }
+
+    fn split_map(value: &str, pair_sep: &str, entry_sep: &str) -> Vec<(String, String)> {
+        parts(value, entry_sep).iter().map(|entry| split_pair(entry, pair_sep).unwrap()).collect()
+    }
+
+    #[test]
+    fn map_round_trip() {
+        let entries =
+            [("a:b", "x,y"), ("url", "http://host:80"), ("\"q\"", " v "), ("", ""), ("k", "a;b\\"), ("nl", "a\nb")];
+        let entries = entries.map(|(k, v)| (k.to_owned(), v.to_owned()));
+        for (pair_sep, entry_sep) in [(":", ", "), ("=", "|"), (":", " ")] {
+            let joined = join_map(entries.clone(), pair_sep, entry_sep);
+            assert_eq!(split_map(&joined, pair_sep, entry_sep), entries, "{}", joined);
+        }
+    }
+}
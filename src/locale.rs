@@ -0,0 +1,47 @@
+//! Locale module
+//!
+//! Contains `fallbacks` routine which lists locale-suffixed keys to look up for given locale
+//! in freedesktop order
+
+/// Locales to look up for `locale` (`lang_COUNTRY.ENCODING@MODIFIER`) in order of preference:
+/// `lang_COUNTRY@MODIFIER`, `lang_COUNTRY`, `lang@MODIFIER` and `lang`. Encoding is ignored.
+pub fn fallbacks(locale: &str) -> Vec<String> {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, Some(modifier)),
+        None => (locale, None),
+    };
+    let rest = rest.split('.').next().unwrap_or_default();
+    let (lang, country) = match rest.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (rest, None),
+    };
+    let mut result = Vec::new();
+    if lang.is_empty() {
+        return result;
+    }
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        result.push(format!("{}_{}@{}", lang, country, modifier));
+    }
+    if let Some(country) = country {
+        result.push(format!("{}_{}", lang, country));
+    }
+    if let Some(modifier) = modifier {
+        result.push(format!("{}@{}", lang, modifier));
+    }
+    result.push(lang.to_owned());
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn order() {
+        assert_eq!(fallbacks("de_DE.UTF-8@euro"), ["de_DE@euro", "de_DE", "de@euro", "de"]);
+        assert_eq!(fallbacks("de_DE"), ["de_DE", "de"]);
+        assert_eq!(fallbacks("sr@latin"), ["sr@latin", "sr"]);
+        assert_eq!(fallbacks("C"), ["C"]);
+        assert!(fallbacks("").is_empty());
+    }
+}
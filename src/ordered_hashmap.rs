@@ -4,24 +4,42 @@
 //! e.g. for save ini sections and items in the same order as loaded or added
 
 use std::borrow::Borrow;
-use std::collections::hash_map::{self, Entry};
-use std::collections::HashMap;
-use std::hash::Hash;
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::iter::FromIterator;
 use std::iter::IntoIterator;
 
-/// Ordered hashmap built on top of std::collections::HashMap
-/// Keys are stored in the field `keys` in the order they were added
-#[derive(Debug)]
+/// Marker of empty slot in the index table
+const EMPTY: usize = usize::MAX;
+
+/// Ordered hashmap with keys stored only once
+///
+/// Entries are stored in the field `entries` in the order they were added together with hashes
+/// of their keys, `slots` is an open addressing table of positions in `entries` (linear probing,
+/// its length is zero or a power of two). Removed entries leave holes (`None`), which are compacted
+/// away when they take more than half of `entries`, so removal is amortized O(1) and keeps order.
+#[derive(Clone)]
 pub struct OrderedHashMap<K, V> {
     #[doc(hidden)]
-    base: HashMap<K, V>,
-    keys: Vec<K>,
+    entries: Vec<Option<Bucket<K, V>>>,
+    slots: Vec<usize>,
+    len: usize,
+    hasher: RandomState,
+}
+
+/// Entry of the map with hash of its key
+#[derive(Clone)]
+struct Bucket<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
 }
 
 impl<K, V> OrderedHashMap<K, V>
 where
-    K: Eq + Hash + Clone,
+    K: Eq + Hash,
 {
     /// Creates an empty `OrderedHashMap`.
     ///
@@ -31,7 +49,89 @@ where
     /// let mut map: OrderedHashMap<&str, i32> = HashMap::new();
     /// ```
     pub fn new() -> OrderedHashMap<K, V> {
-        OrderedHashMap { base: HashMap::<K, V>::new(), keys: Vec::<K>::new() }
+        OrderedHashMap { entries: Vec::new(), slots: Vec::new(), len: 0, hasher: RandomState::new() }
+    }
+
+    /// Reserves capacity for at least `additional` more elements.
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+        if (self.len + additional) * 4 > self.slots.len() * 3 {
+            self.rebuild(self.len + additional);
+        }
+    }
+
+    fn hash<Q: Hash + ?Sized>(&self, k: &Q) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        k.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Position in `slots` of key `k` with `hash`, or position of the empty slot where it belongs,
+    /// `slots` must not be empty
+    fn find<Q>(&self, hash: u64, k: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let mask = self.slots.len() - 1;
+        let mut position = hash as usize & mask;
+        loop {
+            match self.slots[position] {
+                EMPTY => return Err(position),
+                index => {
+                    let bucket = bucket(&self.entries, index);
+                    if bucket.hash == hash && bucket.key.borrow() == k {
+                        return Ok(position);
+                    }
+                }
+            }
+            position = (position + 1) & mask;
+        }
+    }
+
+    /// Position in `entries` of key `k`, empty map is not hashed
+    fn index_of<Q>(&self, k: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let position = self.find(self.hash(k), k).ok()?;
+        Some(self.slots[position])
+    }
+
+    /// Removes slot at `position` shifting back the following slots of the same probe sequence
+    fn remove_slot(&mut self, position: usize) {
+        let mask = self.slots.len() - 1;
+        let mut hole = position;
+        let mut next = (position + 1) & mask;
+        while self.slots[next] != EMPTY {
+            let home = bucket(&self.entries, self.slots[next]).hash as usize & mask;
+            // slot can fill the hole only if the hole is between its home and its position
+            if next.wrapping_sub(home) & mask >= next.wrapping_sub(hole) & mask {
+                self.slots[hole] = self.slots[next];
+                hole = next;
+            }
+            next = (next + 1) & mask;
+        }
+        self.slots[hole] = EMPTY;
+    }
+
+    /// Fills `slots` with positions of all entries, the table is grown to fit `capacity` entries
+    fn rebuild(&mut self, capacity: usize) {
+        let mut size = self.slots.len().max(8);
+        while capacity * 4 > size * 3 {
+            size *= 2;
+        }
+        self.slots.clear();
+        self.slots.resize(size, EMPTY);
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let Some(bucket) = entry {
+                place(&mut self.slots, bucket.hash, index);
+            }
+        }
     }
 
     /// Returns a reference to the value corresponding to the key.
@@ -53,7 +153,8 @@ where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        self.base.get(k)
+        let index = self.index_of(k)?;
+        self.entries[index].as_ref().map(|bucket| &bucket.value)
     }
 
     /// Returns a mutable reference to the value corresponding to the key.
@@ -79,7 +180,18 @@ where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        self.base.get_mut(k)
+        let index = self.index_of(k)?;
+        self.entries[index].as_mut().map(|bucket| &mut bucket.value)
+    }
+
+    /// Returns the number of elements in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
     pub fn contains_key<Q>(&self, k: &Q) -> bool
@@ -87,7 +199,7 @@ where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        self.base.contains_key(k)
+        self.index_of(k).is_some()
     }
 
     /// Inserts a key-value pair into the map.
@@ -110,14 +222,32 @@ where
     /// assert_eq!(map[&37], "c");
     /// ```
     pub fn insert(&mut self, k: K, v: V) -> Option<V> {
-        if !self.base.contains_key(&k) {
-            self.keys.push(k.clone());
+        match self.entry(k) {
+            Entry::Occupied(mut entry) => Some(std::mem::replace(entry.get_mut(), v)),
+            Entry::Vacant(entry) => {
+                entry.insert(v);
+                None
+            }
+        }
+    }
+
+    /// Appends new entry, key must not be in the map
+    fn push(&mut self, hash: u64, k: K, v: V) -> &mut V {
+        let index = self.entries.len();
+        if (self.len + 1) * 4 > self.slots.len() * 3 {
+            self.rebuild(self.len + 1);
+        }
+        place(&mut self.slots, hash, index);
+        self.entries.push(Some(Bucket { hash, key: k, value: v }));
+        self.len += 1;
+        match &mut self.entries[index] {
+            Some(bucket) => &mut bucket.value,
+            None => unreachable!("entry was just pushed"),
         }
-        self.base.insert(k, v)
     }
 
     /// Removes a key from the map, returning the value at the key if the key
-    /// was previously in the map.
+    /// was previously in the map. Order of other keys is preserved.
     ///
     /// # Examples
     ///
@@ -129,16 +259,35 @@ where
     /// ```
     pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
     where
-        K: Borrow<Q> + PartialEq<Q>,
+        K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
     {
-        match self.keys.iter().position(|x| x == k) {
-            Some(index) => {
-                self.keys.swap_remove(index);
-                self.base.remove(k)
-            }
-            None => None,
+        if self.len == 0 {
+            return None;
         }
+        let position = self.find(self.hash(k), k).ok()?;
+        let index = self.slots[position];
+        self.remove_slot(position);
+        let entry = self.entries[index].take();
+        self.len -= 1;
+        self.shrink();
+        entry.map(|bucket| bucket.value)
+    }
+
+    /// Compacts `entries` if holes take more than half of it
+    fn shrink(&mut self) {
+        if self.len * 2 < self.entries.len() {
+            self.compact();
+        }
+    }
+
+    /// Removes holes from `entries` and updates `slots`
+    fn compact(&mut self) {
+        if self.len == self.entries.len() {
+            return;
+        }
+        self.entries.retain(Option::is_some);
+        self.rebuild(self.len);
     }
 
     /// An iterator visiting all key-value pairs in the order they were added.
@@ -157,7 +306,7 @@ where
     /// }
     /// ```
     pub fn iter(&self) -> Iter<'_, K, V> {
-        Iter { base: &self.base, keys_iterator: self.keys.iter() }
+        Iter { entries: self.entries.iter(), remaining: self.len }
     }
 
     /// An iterator visiting all key-value pairs in the order they were added,
@@ -182,7 +331,7 @@ where
     /// }
     /// ```
     pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
-        self.base.iter_mut()
+        IterMut { entries: self.entries.iter_mut(), remaining: self.len }
     }
 
     /// An iterator visiting all keys in the order they were added.
@@ -200,8 +349,137 @@ where
     ///     println!("{}", key);
     /// }
     /// ```
-    pub fn keys(&self) -> std::slice::Iter<K> {
-        self.keys.iter()
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { iter: self.iter() }
+    }
+
+    /// Replaces key `k` with `new` keeping its position and value.
+    ///
+    /// Returns `false` if `k` is not in the map or `new` is already used by another element.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut map = OrderedHashMap::new();
+    /// map.insert("a", 1);
+    /// map.insert("b", 2);
+    /// assert!(map.rename("a", "c"));
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), [&"c", &"b"]);
+    /// ```
+    pub fn rename<Q>(&mut self, k: &Q, new: K) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if new.borrow() == k {
+            return self.contains_key(k);
+        }
+        if self.contains_key::<K>(&new) || !self.contains_key(k) {
+            return false;
+        }
+        let position = self.find(self.hash(k), k).unwrap_or_else(|_| unreachable!("key is in the map"));
+        let index = self.slots[position];
+        self.remove_slot(position);
+        let hash = self.hash(&new);
+        if let Some(bucket) = &mut self.entries[index] {
+            bucket.hash = hash;
+            bucket.key = new;
+        }
+        place(&mut self.slots, hash, index);
+        true
+    }
+
+    /// Inserts a key-value pair right before `anchor`, moving the key if it is already in the map.
+    ///
+    /// Returns `false` and leaves the map unchanged if `anchor` is not in the map or equals to `k`.
+    pub fn insert_before<Q>(&mut self, anchor: &Q, k: K, v: V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.insert_relative(anchor, k, v, 0)
+    }
+
+    /// Inserts a key-value pair right after `anchor`, moving the key if it is already in the map.
+    ///
+    /// Returns `false` and leaves the map unchanged if `anchor` is not in the map or equals to `k`.
+    pub fn insert_after<Q>(&mut self, anchor: &Q, k: K, v: V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.insert_relative(anchor, k, v, 1)
+    }
+
+    fn insert_relative<Q>(&mut self, anchor: &Q, k: K, v: V, offset: usize) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if k.borrow() == anchor || !self.contains_key(anchor) {
+            return false;
+        }
+        if let Some(index) = self.index_of::<K>(&k) {
+            self.entries[index] = None;
+            self.len -= 1;
+        }
+        self.entries.retain(Option::is_some);
+        let index = self.entries.iter().flatten().position(|bucket| bucket.key.borrow() == anchor);
+        let index = index.unwrap_or_else(|| unreachable!("anchor is in the map")) + offset;
+        let hash = self.hash(&k);
+        self.entries.insert(index, Some(Bucket { hash, key: k, value: v }));
+        self.len += 1;
+        self.rebuild(self.len);
+        true
+    }
+
+    /// Sorts the map in place with comparator function, which receives two key-value pairs.
+    ///
+    /// The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut map = OrderedHashMap::new();
+    /// map.insert("b", 1);
+    /// map.insert("a", 2);
+    /// map.sort_by(|k1, _, k2, _| k1.cmp(k2));
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), [&"a", &"b"]);
+    /// ```
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        self.entries.retain(Option::is_some);
+        self.entries.sort_by(|a, b| match (a, b) {
+            (Some(a), Some(b)) => cmp(&a.key, &a.value, &b.key, &b.value),
+            _ => Ordering::Equal,
+        });
+        self.rebuild(self.len);
+    }
+
+    /// Retains only the elements specified by the predicate, keeping order of the rest.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut map: OrderedHashMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+    /// map.retain(|&k, _| k % 2 == 0);
+    /// assert_eq!(map.len(), 4);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        for entry in self.entries.iter_mut() {
+            if let Some(bucket) = entry {
+                if !f(&bucket.key, &mut bucket.value) {
+                    *entry = None;
+                    self.len -= 1;
+                }
+            }
+        }
+        self.compact();
     }
 
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
@@ -222,22 +500,76 @@ where
     /// assert_eq!(letters.get(&'y'), None);
     /// ```
     pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
-        if !self.base.contains_key(&key) {
-            self.keys.push(key.clone());
+        let hash = self.hash(&key);
+        let found = match self.len {
+            0 => None,
+            _ => self.find(hash, &key).ok(),
+        };
+        match found {
+            Some(position) => match &mut self.entries[self.slots[position]] {
+                Some(bucket) => Entry::Occupied(OccupiedEntry { bucket }),
+                None => unreachable!("slot points to removed entry"),
+            },
+            None => Entry::Vacant(VacantEntry { hash, key, map: self }),
         }
-        self.base.entry(key)
     }
 }
 
+/// Bucket at `index` which is referenced by a slot
+fn bucket<K, V>(entries: &[Option<Bucket<K, V>>], index: usize) -> &Bucket<K, V> {
+    match &entries[index] {
+        Some(bucket) => bucket,
+        None => unreachable!("slot points to removed entry"),
+    }
+}
+
+/// Stores `index` with `hash` into the first empty slot of its probe sequence
+fn place(slots: &mut [usize], hash: u64, index: usize) {
+    let mask = slots.len() - 1;
+    let mut position = hash as usize & mask;
+    while slots[position] != EMPTY {
+        position = (position + 1) & mask;
+    }
+    slots[position] = index;
+}
+
 impl<K, V> Default for OrderedHashMap<K, V>
 where
-    K: Eq + Hash + Clone,
+    K: Eq + Hash,
 {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl<K, V> fmt::Debug for OrderedHashMap<K, V>
+where
+    K: Eq + Hash + fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Maps are equal if they have equal key-value pairs in the same order
+impl<K, V> PartialEq for OrderedHashMap<K, V>
+where
+    K: Eq + Hash,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V> Eq for OrderedHashMap<K, V>
+where
+    K: Eq + Hash,
+    V: Eq,
+{
+}
+
 impl<'a, K, V> IntoIterator for &'a OrderedHashMap<K, V>
 where
     K: Eq + Hash,
@@ -246,13 +578,25 @@ where
     type IntoIter = Iter<'a, K, V>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter { base: &self.base, keys_iterator: self.keys.iter() }
+        self.iter()
+    }
+}
+
+impl<K, V> IntoIterator for OrderedHashMap<K, V>
+where
+    K: Eq + Hash,
+{
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { remaining: self.len, entries: self.entries.into_iter() }
     }
 }
 
 impl<K, V> FromIterator<(K, V)> for OrderedHashMap<K, V>
 where
-    K: Eq + Hash + Clone,
+    K: Eq + Hash,
 {
     fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
         let mut map = OrderedHashMap::new();
@@ -277,20 +621,20 @@ where
 /// ```
 pub struct Iter<'a, K, V> {
     #[doc(hidden)]
-    base: &'a HashMap<K, V>,
-    keys_iterator: std::slice::Iter<'a, K>,
+    entries: std::slice::Iter<'a, Option<Bucket<K, V>>>,
+    remaining: usize,
 }
 
-impl<'a, K, V> Iterator for Iter<'a, K, V>
-where
-    K: Eq + Hash,
-{
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
     type Item = (&'a K, &'a V);
     fn next(&mut self) -> Option<Self::Item> {
-        match self.keys_iterator.next() {
-            Some(k) => self.base.get_key_value(&k),
-            None => None,
-        }
+        let bucket = self.entries.find_map(Option::as_ref)?;
+        self.remaining -= 1;
+        Some((&bucket.key, &bucket.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 
@@ -308,26 +652,156 @@ where
 /// ```
 pub struct IntoIter<K, V> {
     #[doc(hidden)]
-    base: HashMap<K, V>,
-    keys_iterator: std::vec::IntoIter<K>,
+    entries: std::vec::IntoIter<Option<Bucket<K, V>>>,
+    remaining: usize,
 }
 
-impl<K, V> Iterator for IntoIter<K, V>
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let bucket = self.entries.find_map(|entry| entry)?;
+        self.remaining -= 1;
+        Some((bucket.key, bucket.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// A mutable iterator over the entries of a `OrderedHashMap` in the order they were added.
+///
+/// This `struct` is created by the `iter_mut` method on `OrderedHashMap`.
+pub struct IterMut<'a, K, V> {
+    #[doc(hidden)]
+    entries: std::slice::IterMut<'a, Option<Bucket<K, V>>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+    fn next(&mut self) -> Option<Self::Item> {
+        let bucket = self.entries.find_map(Option::as_mut)?;
+        self.remaining -= 1;
+        Some((&bucket.key, &mut bucket.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+/// An iterator over the keys of a `OrderedHashMap` in the order they were added.
+///
+/// This `struct` is created by the `keys` method on `OrderedHashMap`.
+pub struct Keys<'a, K, V> {
+    #[doc(hidden)]
+    iter: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// A view into a single entry in a map, which may either be vacant or occupied.
+///
+/// This `enum` is constructed from the `entry` method on `OrderedHashMap`.
+/// The key is added to the map only when vacant entry is filled.
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+/// A view into an occupied entry in a `OrderedHashMap`. It is part of the `Entry` enum.
+pub struct OccupiedEntry<'a, K, V> {
+    #[doc(hidden)]
+    bucket: &'a mut Bucket<K, V>,
+}
+
+/// A view into a vacant entry in a `OrderedHashMap`. It is part of the `Entry` enum.
+pub struct VacantEntry<'a, K, V> {
+    #[doc(hidden)]
+    hash: u64,
+    key: K,
+    map: &'a mut OrderedHashMap<K, V>,
+}
+
+impl<'a, K, V> Entry<'a, K, V>
 where
     K: Eq + Hash,
 {
-    type Item = (K, V);
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.keys_iterator.next() {
-            Some(k) => self.base.remove_entry(&k),
-            None => None,
+    /// Ensures a value is in the entry by inserting the default if empty, and returns
+    /// a mutable reference to the value in the entry.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
         }
     }
+
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    /// Gets a reference to the key in the entry.
+    pub fn key(&self) -> &K {
+        &self.bucket.key
+    }
+
+    /// Gets a reference to the value in the entry.
+    pub fn get(&self) -> &V {
+        &self.bucket.value
+    }
+
+    /// Gets a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.bucket.value
+    }
+
+    /// Converts the entry into a mutable reference to the value with a lifetime bound to the map itself.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.bucket.value
+    }
 }
 
-/// A mutable iterator over the entries of a `OrderedHashMap`.
-/// Note that it iterates in arbitrary order.
-pub type IterMut<'a, K, V> = hash_map::IterMut<'a, K, V>;
+impl<'a, K, V> VacantEntry<'a, K, V>
+where
+    K: Eq + Hash,
+{
+    /// Gets a reference to the key that would be used when inserting a value through the `VacantEntry`.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Sets the value of the entry, appending the key to the end of the map,
+    /// and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.push(self.hash, self.key, value)
+    }
+}
 
 #[cfg(test)]
 mod library_test {
@@ -340,4 +814,98 @@ mod library_test {
         assert_eq!(map.get("a"), Some(&1));
         assert_eq!(map.get("b"), None);
     }
+
+    #[test]
+    fn entry() {
+        let mut map = OrderedHashMap::new();
+        map.insert("a", 1);
+        *map.entry("b").or_insert(0) += 2;
+        *map.entry("a").or_insert(0) += 2;
+        // vacant entry which wasn't filled doesn't add a key
+        assert!(matches!(map.entry("c"), Entry::Vacant(_)));
+        let pairs: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(pairs, [("a", 3), ("b", 2)]);
+    }
+
+    #[test]
+    fn reorder() {
+        let mut map: OrderedHashMap<&str, i32> = vec![("a", 1), ("b", 2), ("c", 3)].into_iter().collect();
+        assert!(map.rename("b", "x"));
+        assert!(!map.rename("x", "a"));
+        assert!(!map.rename("b", "y"));
+        assert!(map.insert_before("a", "c", 30));
+        assert!(map.insert_after("x", "d", 4));
+        assert!(!map.insert_after("e", "f", 5));
+        assert!(!map.insert_after("d", "d", 5));
+        let pairs: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(pairs, [("c", 30), ("a", 1), ("x", 2), ("d", 4)]);
+        map.sort_by(|_, v1, _, v2| v1.cmp(v2));
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), ["a", "x", "d", "c"]);
+    }
+
+    #[test]
+    fn remove_keeps_order() {
+        let mut map: OrderedHashMap<u32, u32> = (0..100).map(|x| (x, x)).collect();
+        for k in (0..100).filter(|k| k % 3 != 0) {
+            assert_eq!(map.remove(&k), Some(k));
+        }
+        assert_eq!(map.remove(&1), None);
+        assert_eq!(map.len(), 34);
+        assert!(map.keys().copied().eq((0..100).step_by(3)));
+        // indices stay valid after compaction
+        assert!((0..100).step_by(3).all(|k| map.get(&k) == Some(&k)));
+        map.insert(1, 1);
+        assert_eq!(map.keys().last(), Some(&1));
+    }
+
+    #[test]
+    fn same_as_vec() {
+        // pseudo-random operations on small key space, so probe sequences collide and get shifted back
+        let mut map = OrderedHashMap::new();
+        let mut model: Vec<(u32, u32)> = Vec::new();
+        let mut seed = 1u32;
+        for step in 0..5000 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let key = (seed >> 16) % 64;
+            match step % 5 {
+                0 | 1 => {
+                    let old = model.iter_mut().find(|(k, _)| *k == key).map(|(_, v)| std::mem::replace(v, step));
+                    if old.is_none() {
+                        model.push((key, step));
+                    }
+                    assert_eq!(map.insert(key, step), old);
+                }
+                2 | 3 => {
+                    let old = model.iter().position(|(k, _)| *k == key).map(|i| model.remove(i).1);
+                    assert_eq!(map.remove(&key), old);
+                }
+                _ => {
+                    let new = key + 64;
+                    let renamed = model.iter_mut().find(|(k, _)| *k == key).map(|(k, _)| *k = new).is_some();
+                    assert_eq!(map.rename(&key, new), renamed);
+                    map.rename(&new, key);
+                    if let Some((k, _)) = model.iter_mut().find(|(k, _)| *k == new) {
+                        *k = key;
+                    }
+                }
+            }
+            assert_eq!(map.len(), model.len());
+            assert!((0..128).all(|k| map.get(&k) == model.iter().find(|(m, _)| *m == k).map(|(_, v)| v)));
+        }
+        assert!(map.into_iter().eq(model));
+    }
+
+    #[test]
+    fn ordered_iter_mut() {
+        let mut map: OrderedHashMap<u32, u32> = (0..50).rev().map(|x| (x, 0)).collect();
+        map.remove(&10);
+        for (index, (_, v)) in map.iter_mut().enumerate() {
+            *v = index as u32;
+        }
+        let pairs: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        let expected: Vec<_> = (0..50).rev().filter(|&k| k != 10).enumerate().map(|(i, k)| (k, i as u32)).collect();
+        assert_eq!(pairs, expected);
+        map.retain(|k, _| k % 2 == 0);
+        assert!(map.into_iter().map(|(k, _)| k).eq((0..50).rev().filter(|&k| k != 10 && k % 2 == 0)));
+    }
 }
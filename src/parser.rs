@@ -1,66 +1,426 @@
 //! Parser module
 //!
-//! Contains `parse_line` routine to parse single line of ini file
-//! and `Parsed` enum for parsing result
+//! Contains `parse_line` routine to parse single line of ini file,
+//! `Parsed` enum for parsing result, [ParseOptions] for parsing modes
+//! and `Builder` which collects parsed lines into [Ini]
+use crate::case::Aliases;
 use crate::error::ParseError;
+use crate::list;
+use crate::ordered_hashmap::OrderedHashMap;
+use crate::{Ini, Section};
+use std::iter;
+use std::mem;
 
-/// Enum for storing one of 4 possible `parse_line` results
+/// Options which change how ini files are parsed
+///
+/// # Example
+/// ```
+/// # use tini::{Ini, ParseOptions};
+/// let options = ParseOptions::new().php_arrays(true);
+/// let conf = Ini::from_string_with("[php]\nextension[] = gd\nextension[] = curl", &options).unwrap();
+///
+/// assert_eq!(conf.get_vec::<String>("php", "extension").unwrap(), ["gd", "curl"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub(crate) php_arrays: bool,
+    pub(crate) case_insensitive: bool,
+}
+
+impl ParseOptions {
+    /// Create default options (similar to [ParseOptions::default])
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Group php.ini-style array keys: `name[] = a` lines are collected into list value of `name`
+    /// (readable with [Ini::get_vec](crate::Ini::get_vec)) and `name[key] = b` lines are collected
+    /// into inline map value of `name` (readable with [Ini::get_map](crate::Ini::get_map)).
+    ///
+    /// Such keys are written back in the same bracketed form.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, ParseOptions};
+    /// use std::collections::HashMap;
+    ///
+    /// let text = "[php]\nopts[debug] = 1\nopts[level] = 3\n";
+    /// let conf = Ini::from_string_with(text, &ParseOptions::new().php_arrays(true)).unwrap();
+    ///
+    /// let opts: HashMap<String, u8> = conf.get_map("php", "opts").unwrap();
+    /// assert_eq!(opts["level"], 3);
+    /// assert_eq!(conf.to_string(), "[php]\nopts[debug] = 1\nopts[level] = 3\n");
+    /// ```
+    pub fn php_arrays(mut self, enable: bool) -> Self {
+        self.php_arrays = enable;
+        self
+    }
+
+    /// Treat section and key names which differ only by case as the same name, see
+    /// [Ini::new_case_insensitive](crate::Ini::new_case_insensitive)
+    ///
+    /// Duplicates are merged like exact duplicates: sections are joined and the last value of a key wins,
+    /// while the spelling seen first is kept.
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, ParseOptions};
+    /// let text = "[Global]\nWorkgroup = A\n[global]\nworkgroup = B\nsecurity = user";
+    /// let conf = Ini::from_string_with(text, &ParseOptions::new().case_insensitive(true)).unwrap();
+    ///
+    /// assert_eq!(conf.get("GLOBAL", "WORKGROUP"), Some("B".to_owned()));
+    /// assert_eq!(conf.to_string(), "[Global]\nWorkgroup = B\nsecurity = user\n");
+    /// ```
+    pub fn case_insensitive(mut self, enable: bool) -> Self {
+        self.case_insensitive = enable;
+        self
+    }
+}
+
+/// Kind of php-style array key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayKind {
+    /// `name[] = value`
+    List,
+    /// `name[key] = value`
+    Map,
+}
+
+/// Values of php-style array key collected while parsing
 #[derive(Debug)]
-pub enum Parsed {
+pub enum PhpArray {
+    List(Vec<String>),
+    Map(OrderedHashMap<String, String>),
+}
+
+impl PhpArray {
+    pub fn new() -> Self {
+        PhpArray::List(Vec::new())
+    }
+
+    /// Add value with `index` from brackets, empty index means next element
+    pub fn push(&mut self, index: &str, value: String) {
+        if let PhpArray::List(values) = self {
+            if index.is_empty() {
+                values.push(value);
+                return;
+            }
+            // list with explicit index becomes map with numeric keys
+            let map = values.drain(..).enumerate().map(|(i, v)| (i.to_string(), v)).collect();
+            *self = PhpArray::Map(map);
+        }
+        if let PhpArray::Map(map) = self {
+            if index.is_empty() {
+                let next = map.keys().filter_map(|k| k.parse::<usize>().ok()).max().map_or(0, |max| max + 1);
+                map.insert(next.to_string(), value);
+            } else {
+                map.insert(index.to_owned(), value);
+            }
+        }
+    }
+
+    pub fn kind(&self) -> ArrayKind {
+        match self {
+            PhpArray::List(_) => ArrayKind::List,
+            PhpArray::Map(_) => ArrayKind::Map,
+        }
+    }
+
+    /// Raw value in list or inline map syntax
+    pub fn to_value(&self) -> String {
+        match self {
+            PhpArray::List(values) => list::join(values, ", "),
+            PhpArray::Map(map) => list::join_map(map.iter(), ":", ", "),
+        }
+    }
+}
+
+/// Split php-style array key `name[index]` into name and index
+pub fn split_array_key(key: &str) -> Option<(&str, &str)> {
+    let open = key.find('[')?;
+    let index = key[open + 1..].strip_suffix(']')?;
+    let name = key[..open].trim_end();
+    if name.is_empty() || index.contains(['[', ']']) {
+        return None;
+    }
+    Some((name, index.trim()))
+}
+
+/// Enum for storing one of possible `parse_line` results, borrowed from the line
+#[derive(Debug)]
+pub enum Parsed<'a> {
     /// empty line
     Empty,
+    /// ; comment line
+    Comment(&'a str),
     /// [section]
-    Section(String),
+    Section(&'a str),
     /// item = value
-    Value(String, String),
+    Value(&'a str, &'a str),
 }
 
 /// parse single line of ini file
-pub fn parse_line(line: &str, index: usize) -> Result<Parsed, ParseError> {
-    let content = match line.split(&[';', '#'][..]).next() {
-        Some(value) => value.trim(),
+///
+/// Line is scanned byte by byte only once to find the inline comment and the first `=`,
+/// all delimiters are ASCII so every found index is a char boundary. `;` and `#` inside quoted
+/// parts of value don't start a comment unless the quote is never closed.
+pub fn parse_line(line: &str, index: usize) -> Result<Parsed<'_>, ParseError> {
+    let line = line.trim_start();
+    let bytes = line.as_bytes();
+    match bytes.first() {
         None => return Ok(Parsed::Empty),
-    };
-    if content.is_empty() {
-        return Ok(Parsed::Empty);
+        Some(b';' | b'#') => {
+            let text = &line[1..];
+            return Ok(Parsed::Comment(text.strip_prefix(' ').unwrap_or(text).trim_end()));
+        }
+        Some(_) => (),
+    }
+    let mut end = bytes.len();
+    let mut delimiter = None;
+    // first comment character inside quotes, it ends the line if the quote is never closed
+    let mut quoted_comment = None;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, &byte) in bytes.iter().enumerate() {
+        if quoted {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => quoted = false,
+                b';' | b'#' => _ = quoted_comment.get_or_insert(i),
+                _ => (),
+            }
+            continue;
+        }
+        match byte {
+            b';' | b'#' => {
+                end = i;
+                break;
+            }
+            b'=' if delimiter.is_none() => delimiter = Some(i),
+            b'"' if delimiter.is_some() => quoted = true,
+            _ => (),
+        }
+    }
+    if let (true, Some(i)) = (quoted, quoted_comment) {
+        end = i;
     }
+    let content = line[..end].trim_end();
     // add checks for content
     if content.starts_with('[') {
         if content.ends_with(']') {
-            let section_name = content.trim_matches(|c| c == '[' || c == ']').to_owned();
-            return Ok(Parsed::Section(section_name));
+            return Ok(Parsed::Section(content.trim_matches(|c| c == '[' || c == ']')));
         }
         return Err(ParseError::IncorrectSection(index));
     }
-    if content.contains('=') {
-        let mut pair = content.splitn(2, '=').map(|s| s.trim());
-        // if key is None => error
-        let key = match pair.next() {
-            Some(value) => value.to_owned(),
-            None => return Err(ParseError::EmptyKey(index)),
-        };
-        if key.is_empty() {
-            return Err(ParseError::EmptyKey(index));
-        }
-        // if value is None => empty string
-        let value = match pair.next() {
-            Some(value) => value.to_owned(),
-            None => "".to_owned(),
-        };
-        return Ok(Parsed::Value(key, value));
-    }
-    Err(ParseError::IncorrectSyntax(index))
+    match delimiter {
+        Some(i) => {
+            let key = content[..i].trim_end();
+            if key.is_empty() {
+                return Err(ParseError::EmptyKey(index));
+            }
+            // if value is None => empty string
+            let value = content.get(i + 1..).unwrap_or("").trim();
+            Ok(Parsed::Value(key, value))
+        }
+        None => Err(ParseError::IncorrectSyntax(index)),
+    }
+}
+
+/// Document which is being parsed, it receives parsed lines one by one
+///
+/// Keys are added to the current section which is stored to the document only when the next section
+/// starts, so every key is hashed and copied once.
+pub struct Builder<'o> {
+    options: &'o ParseOptions,
+    result: Ini,
+    /// name and keys of the current section
+    section: (String, Section),
+    /// php-style arrays of every section, they are stored to the document after parsing
+    arrays: OrderedHashMap<(String, String), PhpArray>,
+    /// lines of comment block above the next section or key
+    comment: Vec<String>,
+    /// comment blocks separated by an empty line from the following content, they are attached
+    /// to the next section or key, at the end of input they become the footer
+    loose: Vec<String>,
+    prefix: Option<char>,
+    content: bool,
+}
+
+impl<'o> Builder<'o> {
+    pub fn new(options: &'o ParseOptions) -> Self {
+        let aliases = Aliases::new(options.case_insensitive);
+        Builder {
+            options,
+            result: Ini::with_aliases(aliases.clone()),
+            section: (String::new(), Section::with_aliases(aliases)),
+            arrays: OrderedHashMap::new(),
+            comment: Vec::new(),
+            loose: Vec::new(),
+            prefix: None,
+            content: false,
+        }
+    }
+
+    /// Identifier of php-style array `name` in current section
+    fn array_id(&self, name: &str) -> (String, String) {
+        match self.options.case_insensitive {
+            true => (self.section.0.to_lowercase(), name.to_lowercase()),
+            false => (self.section.0.clone(), name.to_owned()),
+        }
+    }
+
+    /// Add `parsed` content of `line`
+    pub fn push(&mut self, parsed: Parsed, line: &str) {
+        match parsed {
+            Parsed::Comment(text) => {
+                self.prefix.get_or_insert_with(|| line.trim_start().chars().next().unwrap_or(';'));
+                self.comment.push(text.to_owned());
+            }
+            Parsed::Section(name) => {
+                self.content = true;
+                self.attach_loose();
+                self.flush();
+                self.section.0.push_str(name);
+                if !self.comment.is_empty() {
+                    self.section.1.set_comment(&self.comment.join("\n"));
+                    self.comment.clear();
+                }
+            }
+            Parsed::Value(name, value) => {
+                self.content = true;
+                self.attach_loose();
+                let key = match split_array_key(name).filter(|_| self.options.php_arrays) {
+                    Some((array, index)) => {
+                        let id = self.array_id(array);
+                        if !self.arrays.contains_key(&id) {
+                            // reserve position of the key in section
+                            self.section.1.insert(array.to_owned(), String::new());
+                        }
+                        self.arrays.entry(id).or_insert_with(PhpArray::new).push(index, value.to_owned());
+                        array
+                    }
+                    None => {
+                        if self.options.php_arrays {
+                            self.arrays.remove(&self.array_id(name));
+                        }
+                        self.section.1.insert(name.to_owned(), value.to_owned());
+                        name
+                    }
+                };
+                if !self.comment.is_empty() {
+                    self.section.1.comment_key(key, &self.comment.join("\n"));
+                    self.comment.clear();
+                }
+            }
+            Parsed::Empty => {
+                // first comment block which is separated from the content is the header
+                if !self.content && self.result.header.is_none() && !self.comment.is_empty() {
+                    self.result.header = Some(self.comment.join("\n"));
+                    self.comment.clear();
+                }
+                self.keep_loose();
+            }
+        }
+    }
+
+    /// Move pending comment block to the loose ones, blocks are separated by an empty line
+    fn keep_loose(&mut self) {
+        if !self.comment.is_empty() {
+            if !self.loose.is_empty() {
+                self.loose.push(String::new());
+            }
+            self.loose.append(&mut self.comment);
+        }
+    }
+
+    /// Prepend loose comment blocks to the comment of the next section or key
+    fn attach_loose(&mut self) {
+        if !self.loose.is_empty() {
+            if !self.comment.is_empty() {
+                self.loose.push(String::new());
+            }
+            self.loose.append(&mut self.comment);
+            mem::swap(&mut self.comment, &mut self.loose);
+        }
+    }
+
+    /// Store current section to the document, it's merged with the section of the same name
+    fn flush(&mut self) {
+        let aliases = Aliases::new(self.options.case_insensitive);
+        let (name, section) = mem::replace(&mut self.section, (String::new(), Section::with_aliases(aliases)));
+        // sections of generated files often have the same size, this saves growing of the next one
+        self.section.1.inner.reserve(section.len());
+        if !section.is_empty() || section.get_comment().is_some() {
+            self.result.extend(iter::once((name, section)));
+        }
+    }
+
+    /// Finish parsing and return the document
+    pub fn finish(mut self) -> Ini {
+        // keep the last section for following builder calls like after `Ini::section`
+        let last_section_name = self.section.0.clone();
+        self.flush();
+        self.result.last_section_name = last_section_name;
+        // comment block at the end of file without content is the header
+        if !self.content && self.result.header.is_none() && !self.comment.is_empty() {
+            self.result.header = Some(self.comment.join("\n"));
+            self.comment.clear();
+        }
+        self.keep_loose();
+        if !self.loose.is_empty() {
+            self.result.footer = Some(self.loose.join("\n"));
+        }
+        if let Some(prefix) = self.prefix {
+            self.result.comment_prefix = prefix.to_string();
+        }
+        for ((section, name), array) in self.arrays {
+            if let Some(section) = self.result.section_mut(&section) {
+                section.insert(name.clone(), array.to_value());
+                let name = section.key(&name).to_owned();
+                section.arrays.insert(name, array.kind());
+            }
+        }
+        self.result
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod test {
     use super::*;
     use crate::error::Error;
 
+    #[test]
+    fn array_key() {
+        assert_eq!(split_array_key("ext[]"), Some(("ext", "")));
+        assert_eq!(split_array_key("opts [ debug ]"), Some(("opts", "debug")));
+        assert_eq!(split_array_key("[]"), None);
+        assert_eq!(split_array_key("a[b][c]"), None);
+        assert_eq!(split_array_key("plain"), None);
+    }
+
+    #[test]
+    fn php_array() {
+        let mut array = PhpArray::new();
+        array.push("", "a".to_owned());
+        array.push("", "b, c".to_owned());
+        assert_eq!(array.kind(), ArrayKind::List);
+        assert_eq!(array.to_value(), "a, \"b, c\"");
+        array.push("x", "1".to_owned());
+        array.push("", "2".to_owned());
+        assert_eq!(array.kind(), ArrayKind::Map);
+        assert_eq!(array.to_value(), "0:a, \"1:b, c\", x:1, 2:2");
+    }
+
     #[test]
     fn comment() -> Result<(), Error> {
         match parse_line(";------", 0)? {
-            Parsed::Empty => assert!(true),
+            Parsed::Comment(text) => assert_eq!(text, "------"),
+            _ => assert!(false),
+        }
+        match parse_line("  #  indented ", 0)? {
+            Parsed::Comment(text) => assert_eq!(text, " indented"),
             _ => assert!(false),
         }
         Ok(())
@@ -78,6 +438,21 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn quoted_comment() -> Result<(), Error> {
+        for (line, expected) in [
+            ("list = \"a;b\", \"c#d\" ; comment", "\"a;b\", \"c#d\""),
+            ("escaped = \"a\\\";b\" # comment", "\"a\\\";b\""),
+            ("open = \"a;b", "\"a"),
+        ] {
+            match parse_line(line, 0)? {
+                Parsed::Value(_, value) => assert_eq!(value, expected),
+                _ => unreachable!(),
+            }
+        }
+        Ok(())
+    }
+
     #[test]
     fn section() -> Result<(), Error> {
         match parse_line("[section]", 0)? {
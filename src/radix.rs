@@ -0,0 +1,166 @@
+//! Radix-aware integers
+//!
+//! Contains [FromIniValue] implementations for primitive integers which accept `0x`, `0o` and `0b` prefixes,
+//! `_` digit separators and an optional sign, and wrappers [Hex], [Octal] and [Binary] to write integers
+//! in a chosen radix
+use crate::error::ValueError;
+use crate::value::{FromIniValue, ToIniValue};
+
+/// Split integer literal into sign, radix and digits without separators
+fn normalize(value: &str) -> Result<(String, u32), ValueError> {
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (radix, digits) = match rest.get(..2) {
+        Some("0x") | Some("0X") => (16, &rest[2..]),
+        Some("0o") | Some("0O") => (8, &rest[2..]),
+        Some("0b") | Some("0B") => (2, &rest[2..]),
+        _ => (10, rest),
+    };
+    if digits.is_empty() {
+        return Err(ValueError::new("cannot parse integer from empty string"));
+    }
+    if digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return Err(ValueError::new("misplaced digit separator `_`"));
+    }
+    if digits.starts_with(['+', '-']) {
+        return Err(ValueError::new("invalid digit found in string"));
+    }
+    Ok((format!("{}{}", sign, digits.replace('_', "")), radix))
+}
+
+macro_rules! impl_radix {
+    (@format $t:ty, $wrapper:ident, $format:literal) => {
+        impl ToIniValue for $wrapper<$t> {
+            #[allow(unused_comparisons)]
+            fn to_ini_value(&self) -> String {
+                // format magnitude with sign instead of two's complement, so value can be read back
+                if self.0 < 0 {
+                    format!($format, "-", (self.0 as i128).unsigned_abs())
+                } else {
+                    format!($format, "", self.0)
+                }
+            }
+        }
+    };
+    ($($t:ty),*) => {
+        $(
+            impl FromIniValue for $t {
+                type Err = ValueError;
+
+                fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+                    let (digits, radix) = normalize(value)?;
+                    <$t>::from_str_radix(&digits, radix).map_err(|e| ValueError::new(e.to_string()))
+                }
+            }
+
+            impl FromIniValue for Hex<$t> {
+                type Err = ValueError;
+
+                fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+                    <$t>::from_ini_value(value).map(Hex)
+                }
+            }
+
+            impl FromIniValue for Octal<$t> {
+                type Err = ValueError;
+
+                fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+                    <$t>::from_ini_value(value).map(Octal)
+                }
+            }
+
+            impl FromIniValue for Binary<$t> {
+                type Err = ValueError;
+
+                fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+                    <$t>::from_ini_value(value).map(Binary)
+                }
+            }
+
+            impl_radix!(@format $t, Hex, "{}{:#x}");
+            impl_radix!(@format $t, Octal, "{}{:#o}");
+            impl_radix!(@format $t, Binary, "{}{:#b}");
+        )*
+    };
+}
+
+/// Integer written in hexadecimal form with `0x` prefix
+///
+/// # Example
+/// ```
+/// # use tini::{Hex, Ini};
+/// let conf = Ini::new().section("hw")
+///                      .item("mask", Hex(0xFF00u32))
+///                      .item_vec("ports", &[Hex(0x3f8u16), Hex(0x2f8)]);
+///
+/// assert_eq!(conf.to_string(), "[hw]\nmask = 0xff00\nports = 0x3f8, 0x2f8\n");
+/// assert_eq!(conf.get("hw", "mask"), Some(0xFF00u32));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hex<T>(pub T);
+
+/// Integer written in octal form with `0o` prefix
+///
+/// # Example
+/// ```
+/// # use tini::{Ini, Octal};
+/// let conf = Ini::new().section("file").item("mode", Octal(0o755u32));
+///
+/// assert_eq!(conf.to_string(), "[file]\nmode = 0o755\n");
+/// assert_eq!(conf.get("file", "mode"), Some(Octal(0o755u32)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Octal<T>(pub T);
+
+/// Integer written in binary form with `0b` prefix
+///
+/// # Example
+/// ```
+/// # use tini::{Binary, Ini};
+/// let conf = Ini::new().section("hw").item("flags", Binary(10u8));
+///
+/// assert_eq!(conf.to_string(), "[hw]\nflags = 0b1010\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Binary<T>(pub T);
+
+impl_radix!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn prefixes() {
+        assert_eq!(u32::from_ini_value("0xFF00").ok(), Some(0xFF00));
+        assert_eq!(u32::from_ini_value("0o755").ok(), Some(0o755));
+        assert_eq!(u8::from_ini_value("0b1010").ok(), Some(10));
+        assert_eq!(u8::from_ini_value("010").ok(), Some(10));
+    }
+
+    #[test]
+    fn separators_and_sign() {
+        assert_eq!(u32::from_ini_value("1_000_000").ok(), Some(1_000_000));
+        assert_eq!(i32::from_ini_value("-0x10").ok(), Some(-16));
+        assert_eq!(i32::from_ini_value("+42").ok(), Some(42));
+        assert_eq!(i8::from_ini_value("-128").ok(), Some(-128));
+        assert!(u8::from_ini_value("-1").is_err());
+        assert!(u8::from_ini_value("256").is_err());
+        assert!(u32::from_ini_value("_1").is_err());
+        assert!(u32::from_ini_value("1__0").is_err());
+        assert!(u32::from_ini_value("0x").is_err());
+        assert!(i32::from_ini_value("--1").is_err());
+        assert!(i32::from_ini_value("0x-1").is_err());
+    }
+
+    #[test]
+    fn output() {
+        assert_eq!(Hex(-16i32).to_ini_value(), "-0x10");
+        assert_eq!(Hex(i8::MIN).to_ini_value(), "-0x80");
+        assert_eq!(Octal(8u8).to_ini_value(), "0o10");
+        assert_eq!(Binary(0u8).to_ini_value(), "0b0");
+        assert_eq!(i8::from_ini_value(&Hex(i8::MIN).to_ini_value()).ok(), Some(i8::MIN));
+    }
+}
@@ -0,0 +1,146 @@
+//! Range lists
+//!
+//! Contains routines to expand range lists like `0-3, 8, 10-11` into integers
+//! and to compress integers back into range notation
+use crate::error::ValueError;
+use crate::value::{FromIniValue, ToIniValue};
+use std::any::type_name;
+
+/// Integer types which can be used in range lists, see [Ini::get_ranges](crate::Ini::get_ranges)
+pub trait RangeInteger: FromIniValue<Err = ValueError> + ToIniValue + Copy + Ord {
+    /// The next integer or [None] on overflow
+    fn successor(self) -> Option<Self>;
+}
+
+macro_rules! impl_range_integer {
+    ($($t:ty),*) => {
+        $(
+            impl RangeInteger for $t {
+                fn successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_range_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// Parse bound of range `element`
+fn bound<T>(element: &str, bound: &str) -> Result<T, ValueError>
+where
+    T: RangeInteger,
+{
+    T::from_ini_value(bound.trim()).map_err(|e| {
+        ValueError::new(format!("invalid bound `{}` of `{}` for {}: {}", bound.trim(), element, type_name::<T>(), e))
+    })
+}
+
+/// Maximum number of integers in expanded range list, so one line can't exhaust memory
+pub(crate) const MAX_EXPANDED: usize = 1 << 20;
+
+/// Expand single element of range list (`8` or `0-3`) and append integers to `output`,
+/// `output` can't grow beyond [MAX_EXPANDED] integers
+pub fn expand<T>(element: &str, output: &mut Vec<T>) -> Result<(), ValueError>
+where
+    T: RangeInteger,
+{
+    // leading `-` is a sign of the start bound
+    let split = element.char_indices().skip(1).find(|&(_, c)| c == '-').map(|(index, _)| index);
+    let (start, end) = match split {
+        Some(index) => (bound(element, &element[..index])?, bound(element, &element[index + 1..])?),
+        None => {
+            let value = bound(element, element)?;
+            (value, value)
+        }
+    };
+    if start > end {
+        return Err(ValueError::new(format!("range `{}` is reversed", element)));
+    }
+    let mut current = start;
+    loop {
+        // checked for every integer because distance between generic bounds is unknown
+        if output.len() >= MAX_EXPANDED {
+            return Err(ValueError::new(format!("range list is longer than {} integers", MAX_EXPANDED)));
+        }
+        output.push(current);
+        if current == end {
+            return Ok(());
+        }
+        current = current.successor().ok_or_else(|| ValueError::new(format!("range `{}` overflows", element)))?;
+    }
+}
+
+/// Compress integers into sorted list of ranges, runs of two or more integers are written as `start-end`
+pub fn compress<T>(values: &[T]) -> Vec<String>
+where
+    T: RangeInteger,
+{
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let mut result = Vec::new();
+    let mut iter = sorted.into_iter().peekable();
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while let Some(&next) = iter.peek() {
+            if end.successor() != Some(next) {
+                break;
+            }
+            end = next;
+            iter.next();
+        }
+        if start == end {
+            result.push(start.to_ini_value());
+        } else {
+            result.push(format!("{}-{}", start.to_ini_value(), end.to_ini_value()));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn expanded<T: RangeInteger>(element: &str) -> Result<Vec<T>, ValueError> {
+        let mut output = Vec::new();
+        expand(element, &mut output).map(|_| output)
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(expanded::<u8>("8"), Ok(vec![8]));
+        assert_eq!(expanded::<u8>("0-3"), Ok(vec![0, 1, 2, 3]));
+        assert_eq!(expanded::<u8>("254 - 255"), Ok(vec![254, 255]));
+        assert_eq!(expanded::<i8>("-2--1"), Ok(vec![-2, -1]));
+        assert_eq!(expanded::<i8>("-1-1"), Ok(vec![-1, 0, 1]));
+        assert_eq!(expanded::<u16>("0x10-0x12"), Ok(vec![16, 17, 18]));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(expanded::<u8>("5-3").is_err());
+        assert!(expanded::<u8>("250-300").is_err());
+        assert!(expanded::<u8>("1-").is_err());
+        assert!(expanded::<u8>("a-b").is_err());
+        assert!(expanded::<u8>("1-2-3").is_err());
+    }
+
+    #[test]
+    fn limit() {
+        assert_eq!(expanded::<u32>("1-1048576").map(|v| v.len()), Ok(MAX_EXPANDED));
+        let error = expanded::<u32>("0-4294967295").err().map(|e| e.to_string());
+        assert_eq!(error.as_deref(), Some("range list is longer than 1048576 integers"));
+        let mut output = vec![0u64; MAX_EXPANDED];
+        assert!(expand("1", &mut output).is_err());
+    }
+
+    #[test]
+    fn compression() {
+        assert_eq!(compress(&[11u8, 0, 1, 2, 3, 8, 10, 3]), ["0-3", "8", "10-11"]);
+        assert_eq!(compress(&[254u8, 255]), ["254-255"]);
+        assert_eq!(compress(&[-1i8, 0, 1]), ["-1-1"]);
+        assert!(compress::<u8>(&[]).is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+//! Reader module
+//!
+//! Contains [IniReader] which reads ini file line by line and yields [Event] for every line
+use crate::error::Error;
+use crate::parser::{parse_line, Parsed};
+use std::io::BufRead;
+
+/// Line of ini file read by [IniReader], `line` is the line number starting from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// `[name]` section header
+    Section { name: String, line: usize },
+    /// `key = value` pair, inline comment is dropped
+    Key { key: String, value: String, line: usize },
+    /// `; text` or `# text` comment line, `text` doesn't include prefix and one space after it
+    Comment { text: String, prefix: char, line: usize },
+    /// empty line
+    Blank { line: usize },
+}
+
+impl Event {
+    /// Line number of the event
+    pub fn line(&self) -> usize {
+        match self {
+            Event::Section { line, .. } | Event::Key { line, .. } | Event::Comment { line, .. } => *line,
+            Event::Blank { line } => *line,
+        }
+    }
+}
+
+/// Iterator over [Event]s of ini file which keeps only one line in memory
+///
+/// Iteration stops after the first error.
+///
+/// # Example
+/// ```
+/// # use tini::{Event, IniReader};
+/// let text = "[server]\nhost = localhost\n\n[database]\nport = 5432\n[cache]\nsize = 1M";
+///
+/// // stop reading at the first key of `database` section
+/// let mut section = String::new();
+/// let port = IniReader::new(text.as_bytes()).find_map(|event| match event {
+///     Ok(Event::Section { name, .. }) => {
+///         section = name;
+///         None
+///     }
+///     Ok(Event::Key { value, line, .. }) if section == "database" => Some((value, line)),
+///     _ => None,
+/// });
+///
+/// assert_eq!(port, Some(("5432".to_owned(), 5)));
+/// ```
+pub struct IniReader<R> {
+    reader: R,
+    buffer: String,
+    line: usize,
+    failed: bool,
+}
+
+impl<R: BufRead> IniReader<R> {
+    /// Create reader of ini file from buffered `reader`
+    pub fn new(reader: R) -> Self {
+        IniReader { reader, buffer: String::new(), line: 0, failed: false }
+    }
+
+    /// Number of lines read so far
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Unwrap underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: BufRead> IniReader<R> {
+    /// Read the next line and parse it in place, returns parsed line and its text
+    pub(crate) fn read_parsed(&mut self) -> Option<Result<(Parsed<'_>, &str), Error>> {
+        if self.failed {
+            return None;
+        }
+        self.buffer.clear();
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => return None,
+            Ok(_) => self.line += 1,
+            Err(e) => {
+                self.failed = true;
+                return Some(Err(e.into()));
+            }
+        }
+        let text = self.buffer.strip_suffix('\n').unwrap_or(&self.buffer);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        match parse_line(text, self.line) {
+            Ok(parsed) => Some(Ok((parsed, text))),
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e.into()))
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for IniReader<R> {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.line + 1;
+        let (parsed, text) = match self.read_parsed()? {
+            Ok(parsed) => parsed,
+            Err(e) => return Some(Err(e)),
+        };
+        let event = match parsed {
+            Parsed::Empty => Event::Blank { line },
+            Parsed::Comment(comment) => {
+                let prefix = text.trim_start().chars().next().unwrap_or(';');
+                Event::Comment { text: comment.to_owned(), prefix, line }
+            }
+            Parsed::Section(name) => Event::Section { name: name.to_owned(), line },
+            Parsed::Value(key, value) => Event::Key { key: key.to_owned(), value: value.to_owned(), line },
+        };
+        Some(Ok(event))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn events() -> Result<(), Error> {
+        let text = "# about\r\n[a]\r\nx = 1 ; inline\r\n\r\n";
+        let events = IniReader::new(text.as_bytes()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            events,
+            [
+                Event::Comment { text: "about".to_owned(), prefix: '#', line: 1 },
+                Event::Section { name: "a".to_owned(), line: 2 },
+                Event::Key { key: "x".to_owned(), value: "1".to_owned(), line: 3 },
+                Event::Blank { line: 4 },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stops_on_error() {
+        let mut reader = IniReader::new("[a]\nbroken\nx = 1".as_bytes());
+        assert!(matches!(reader.next(), Some(Ok(Event::Section { .. }))));
+        assert!(matches!(reader.next(), Some(Err(Error::Parse(_)))));
+        assert!(reader.next().is_none());
+        assert_eq!(reader.line(), 2);
+    }
+}
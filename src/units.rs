@@ -0,0 +1,344 @@
+//! Human-friendly unit values
+//!
+//! Contains [FromIniValue] and [ToIniValue] implementations for [Duration]
+//! and value types [ByteSize] and [Percent]
+use crate::error::ValueError;
+use crate::value::{FromIniValue, ToIniValue};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Duration units with their length in nanoseconds, from the largest to the smallest
+const DURATION_UNITS: [(&str, u128); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// Byte size units with their length in bytes, from the largest to the smallest
+const BYTE_UNITS: [(&str, u64); 13] = [
+    ("EiB", 1 << 60),
+    ("EB", 1_000_000_000_000_000_000),
+    ("PiB", 1 << 50),
+    ("PB", 1_000_000_000_000_000),
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("kB", 1_000),
+    ("B", 1),
+];
+
+/// Split `input` into leading number and the rest of the string
+fn split_number(input: &str) -> (&str, &str) {
+    let end = input.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(input.len());
+    input.split_at(end)
+}
+
+/// Multiply decimal `number` by integer `scale` without losing precision for integers
+fn scale(number: &str, scale: u128) -> Result<u128, ValueError> {
+    let invalid = || ValueError::new(format!("invalid number `{}`", number));
+    let overflow = || ValueError::new("value is too large");
+    match number.split_once('.') {
+        None => number.parse::<u128>().map_err(|_| invalid())?.checked_mul(scale).ok_or_else(overflow),
+        Some((int, frac)) => {
+            if int.is_empty() && frac.is_empty() || frac.contains('.') {
+                return Err(invalid());
+            }
+            let int = if int.is_empty() { 0 } else { int.parse::<u128>().map_err(|_| invalid())? };
+            if !frac.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            // digits beyond nanosecond or byte precision don't matter
+            let frac = &frac[..frac.len().min(18)];
+            let numerator = if frac.is_empty() { 0 } else { frac.parse::<u128>().map_err(|_| invalid())? };
+            let fraction = numerator.checked_mul(scale).ok_or_else(overflow)? / 10u128.pow(frac.len() as u32);
+            int.checked_mul(scale).and_then(|x| x.checked_add(fraction)).ok_or_else(overflow)
+        }
+    }
+}
+
+/// Durations are written as one or more `<number><unit>` pairs, e.g. `30s`, `1.5h` or `1h 30m`.
+/// Supported units are `d`, `h`, `m`, `s`, `ms`, `us` and `ns`.
+///
+/// # Example
+/// ```
+/// # use tini::Ini;
+/// # use std::time::Duration;
+/// let conf = Ini::from_string("[net]\ntimeout = 1h30m").unwrap();
+///
+/// let timeout: Duration = conf.get("net", "timeout").unwrap();
+/// assert_eq!(timeout, Duration::from_secs(5400));
+/// ```
+impl FromIniValue for Duration {
+    type Err = ValueError;
+
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+        let mut rest = value.trim();
+        if rest.is_empty() {
+            return Err(ValueError::new("empty duration"));
+        }
+        let mut nanos = 0u128;
+        while !rest.is_empty() {
+            let (number, tail) = split_number(rest);
+            if number.is_empty() {
+                return Err(ValueError::new(format!("expected number at `{}`", rest)));
+            }
+            let tail = tail.trim_start();
+            let unit_end = tail.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(unit_end);
+            let size = match DURATION_UNITS.iter().find(|(name, _)| *name == unit) {
+                Some((_, size)) => *size,
+                None if unit.is_empty() => return Err(ValueError::new(format!("missing unit after `{}`", number))),
+                None => return Err(ValueError::new(format!("unknown duration unit `{}`", unit))),
+            };
+            nanos = nanos.checked_add(scale(number, size)?).ok_or_else(|| ValueError::new("value is too large"))?;
+            rest = tail.trim_start();
+        }
+        let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| ValueError::new("value is too large"))?;
+        Ok(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+    }
+}
+
+/// Durations are written in compound form with the largest units first, e.g. `1h30m` or `1s500ms`
+///
+/// # Example
+/// ```
+/// # use tini::Ini;
+/// # use std::time::Duration;
+/// let conf = Ini::new().section("net").item("timeout", Duration::from_millis(90_500));
+///
+/// assert_eq!(conf.to_string(), "[net]\ntimeout = 1m30s500ms\n");
+/// ```
+impl ToIniValue for Duration {
+    fn to_ini_value(&self) -> String {
+        let mut nanos = self.as_nanos();
+        if nanos == 0 {
+            return "0s".to_owned();
+        }
+        let mut result = String::new();
+        for (name, size) in DURATION_UNITS.iter() {
+            if nanos >= *size {
+                result.push_str(&format!("{}{}", nanos / size, name));
+                nanos %= size;
+            }
+        }
+        result
+    }
+}
+
+/// Size in bytes, which may be written with SI (`kB`, `MB`, ...) or IEC (`KiB`, `MiB`, ...) units
+///
+/// Units are case-insensitive, bare numbers are bytes. The canonical form uses the largest unit
+/// which represents the value exactly.
+///
+/// # Example
+/// ```
+/// # use tini::{ByteSize, Ini};
+/// let mut conf = Ini::from_string("[cache]\nmemory = 512MiB\ndisk = 1.5 GB").unwrap();
+///
+/// let memory: ByteSize = conf.get("cache", "memory").unwrap();
+/// let disk: ByteSize = conf.get("cache", "disk").unwrap();
+/// assert_eq!(memory.bytes(), 512 * 1024 * 1024);
+/// assert_eq!(disk.bytes(), 1_500_000_000);
+///
+/// conf = conf.section("cache").item("disk", disk);
+/// assert_eq!(conf.to_string(), "[cache]\nmemory = 512MiB\ndisk = 1500MB\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// Size in bytes
+    pub fn bytes(&self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (number, unit) = split_number(s.trim());
+        if number.is_empty() {
+            return Err(ValueError::new(format!("expected number at `{}`", s.trim())));
+        }
+        let unit = unit.trim();
+        let size = if unit.is_empty() {
+            1
+        } else {
+            match BYTE_UNITS.iter().find(|(name, _)| name.eq_ignore_ascii_case(unit)) {
+                Some((_, size)) => *size,
+                None => return Err(ValueError::new(format!("unknown size unit `{}`", unit))),
+            }
+        };
+        let bytes = scale(number, u128::from(size))?;
+        u64::try_from(bytes).map(ByteSize).map_err(|_| ValueError::new("value is too large"))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, size) =
+            BYTE_UNITS.iter().find(|(_, size)| self.0 % *size == 0 && self.0 >= *size).unwrap_or(&("B", 1));
+        write!(f, "{}{}", self.0 / size, name)
+    }
+}
+
+impl FromIniValue for ByteSize {
+    type Err = ValueError;
+
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+        value.parse()
+    }
+}
+
+impl ToIniValue for ByteSize {
+    fn to_ini_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Percentage written with `%` sign, e.g. `75%` or `12.5 %`
+///
+/// # Example
+/// ```
+/// # use tini::{Ini, Percent};
+/// let conf = Ini::from_string("[cache]\nratio = 75%").unwrap();
+///
+/// let ratio: Percent = conf.get("cache", "ratio").unwrap();
+/// assert_eq!(ratio.value(), 75.0);
+/// assert_eq!(ratio.ratio(), 0.75);
+///
+/// let conf = conf.section("cache").item("ratio", Percent::from_ratio(0.5));
+/// assert_eq!(conf.to_string(), "[cache]\nratio = 50%\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Percent(pub f64);
+
+impl Percent {
+    /// Create percentage from fraction, e.g. `0.75` for `75%`
+    ///
+    /// Result is rounded to 9 decimal places to drop floating point noise of multiplication,
+    /// so `from_ratio(0.07)` is written as `7%`.
+    pub fn from_ratio(ratio: f64) -> Self {
+        const SCALE: f64 = 1e9;
+        let value = ratio * 100.0;
+        let rounded = (value * SCALE).round() / SCALE;
+        Percent(if rounded.is_finite() { rounded } else { value })
+    }
+
+    /// Value in percents, e.g. `75.0` for `75%`
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// Value as fraction, e.g. `0.75` for `75%`
+    pub fn ratio(&self) -> f64 {
+        self.0 / 100.0
+    }
+}
+
+impl FromStr for Percent {
+    type Err = ValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = match s.trim().strip_suffix('%') {
+            Some(number) => number.trim_end(),
+            None => return Err(ValueError::new("missing `%` sign")),
+        };
+        match number.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(Percent(value)),
+            _ => Err(ValueError::new(format!("invalid number `{}`", number))),
+        }
+    }
+}
+
+impl fmt::Display for Percent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+impl FromIniValue for Percent {
+    type Err = ValueError;
+
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+        value.parse()
+    }
+}
+
+impl ToIniValue for Percent {
+    fn to_ini_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn duration(value: &str) -> Option<Duration> {
+        Duration::from_ini_value(value).ok()
+    }
+
+    #[test]
+    fn duration_units() {
+        assert_eq!(duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(duration("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(duration("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(duration("1d"), Some(Duration::from_secs(86400)));
+        assert_eq!(duration("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(duration("1h 30m 15s"), Some(Duration::from_secs(5415)));
+    }
+
+    #[test]
+    fn duration_errors() {
+        assert_eq!(duration(""), None);
+        assert_eq!(duration("30"), None);
+        assert_eq!(duration("30 years"), None);
+        assert_eq!(duration("h"), None);
+        assert_eq!(duration("1..5s"), None);
+    }
+
+    #[test]
+    fn duration_round_trip() {
+        for value in ["0s", "1d2h3m4s5ms6us7ns", "1h30m", "500ms"] {
+            assert_eq!(duration(value).map(|d| d.to_ini_value()), Some(value.to_owned()));
+        }
+    }
+
+    #[test]
+    fn byte_size() {
+        assert_eq!("100".parse(), Ok(ByteSize(100)));
+        assert_eq!("1kb".parse(), Ok(ByteSize(1000)));
+        assert_eq!("1KiB".parse(), Ok(ByteSize(1024)));
+        assert_eq!("0.5 GiB".parse(), Ok(ByteSize(1 << 29)));
+        assert!("16EiB".parse::<ByteSize>().is_err());
+        assert!("1 XB".parse::<ByteSize>().is_err());
+        assert_eq!(ByteSize(0).to_string(), "0B");
+        assert_eq!(ByteSize(1023).to_string(), "1023B");
+        assert_eq!(ByteSize(2048).to_string(), "2KiB");
+        assert_eq!(ByteSize(3_000_000).to_string(), "3MB");
+    }
+
+    #[test]
+    fn percent() {
+        assert_eq!("75%".parse(), Ok(Percent(75.0)));
+        assert_eq!("12.5 %".parse(), Ok(Percent(12.5)));
+        assert!("75".parse::<Percent>().is_err());
+        assert!("inf%".parse::<Percent>().is_err());
+        assert_eq!(Percent(12.5).to_string(), "12.5%");
+        assert_eq!(Percent::from_ratio(0.07).to_string(), "7%");
+        assert_eq!(Percent::from_ratio(0.125).to_string(), "12.5%");
+        assert_eq!(Percent::from_ratio(1.0 / 3.0).to_string(), "33.333333333%");
+    }
+}
@@ -0,0 +1,459 @@
+//! Value conversion module
+//!
+//! Contains [FromIniValue] and [ToIniValue] traits which are used by
+//! [Ini](crate::Ini) getters and setters to convert raw values from and to strings
+use crate::error::ValueError;
+use crate::list;
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::num::{NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize};
+use std::num::{NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Conversion from raw ini value to a typed one
+///
+/// It is implemented for all primitive types (integers also accept `0x`, `0o` and `0b` prefixes
+/// and `_` separators, see [Hex](crate::Hex)), [String], [char], [PathBuf], network addresses
+/// and for containers [Option], [Vec] and tuples of supported types.
+///
+/// # Migration from [FromStr](std::str::FromStr)
+/// Getters used to accept any type implementing [FromStr](std::str::FromStr). Types which are not covered
+/// by the implementations above keep working with one line of [impl_ini_value_via_from_str](crate::impl_ini_value_via_from_str):
+/// ```
+/// # use tini::{impl_ini_value_via_from_str, Ini};
+/// # use std::num::ParseIntError;
+/// # use std::str::FromStr;
+/// #[derive(Debug, PartialEq)]
+/// struct Port(u16);
+///
+/// impl FromStr for Port {
+///     type Err = ParseIntError;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         s.parse().map(Port)
+///     }
+/// }
+///
+/// impl_ini_value_via_from_str!(Port);
+///
+/// let conf = Ini::from_string("[server]\nport = 8080").unwrap();
+/// assert_eq!(conf.get("server", "port"), Some(Port(8080)));
+/// ```
+///
+/// # Example
+/// Implement it directly to accept more spellings than [FromStr](std::str::FromStr) does:
+/// ```
+/// # use tini::{FromIniValue, Ini, ValueError};
+/// #[derive(Debug, PartialEq)]
+/// enum Level {
+///     Low,
+///     High,
+/// }
+///
+/// impl FromIniValue for Level {
+///     type Err = ValueError;
+///
+///     fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+///         match value.to_ascii_lowercase().as_str() {
+///             "low" | "l" => Ok(Level::Low),
+///             "high" | "h" => Ok(Level::High),
+///             _ => Err(ValueError::new("expected low or high")),
+///         }
+///     }
+/// }
+///
+/// let conf = Ini::from_string("[log]\nlevel = H").unwrap();
+/// assert_eq!(conf.get("log", "level"), Some(Level::High));
+/// ```
+pub trait FromIniValue: Sized {
+    /// The associated error which can be returned from conversion
+    type Err: fmt::Display;
+
+    /// Convert raw value to `Self`
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err>;
+}
+
+/// Conversion from a typed value to raw ini value
+///
+/// # Migration from [Display](std::fmt::Display)
+/// Setters used to accept any type implementing [Display](std::fmt::Display), such types keep working
+/// with [impl_ini_value_via_display](crate::impl_ini_value_via_display):
+/// ```
+/// # use tini::{impl_ini_value_via_display, Ini};
+/// # use std::fmt;
+/// struct Port(u16);
+///
+/// impl fmt::Display for Port {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         self.0.fmt(f)
+///     }
+/// }
+///
+/// impl_ini_value_via_display!(Port);
+///
+/// let conf = Ini::new().section("server").item("port", Port(8080));
+/// assert_eq!(conf.to_string(), "[server]\nport = 8080\n");
+/// ```
+///
+/// # Example
+/// ```
+/// # use tini::{Ini, ToIniValue};
+/// struct Port(u16);
+///
+/// impl ToIniValue for Port {
+///     fn to_ini_value(&self) -> String {
+///         self.0.to_string()
+///     }
+/// }
+///
+/// let conf = Ini::new().section("server").item("port", Port(8080));
+/// assert_eq!(conf.to_string(), "[server]\nport = 8080\n");
+/// ```
+pub trait ToIniValue {
+    /// Convert `self` to raw value
+    fn to_ini_value(&self) -> String;
+}
+
+/// Implement [FromIniValue] for types with [FromStr](std::str::FromStr) implementation
+///
+/// The error of [FromStr](std::str::FromStr) is returned as is, so it must implement
+/// [Display](std::fmt::Display). See [FromIniValue] for an example.
+#[macro_export]
+macro_rules! impl_ini_value_via_from_str {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl $crate::FromIniValue for $t {
+                type Err = <$t as ::std::str::FromStr>::Err;
+
+                fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+                    value.parse()
+                }
+            }
+        )*
+    };
+}
+
+/// Implement [ToIniValue] for types with [Display](std::fmt::Display) implementation,
+/// see [ToIniValue] for an example
+#[macro_export]
+macro_rules! impl_ini_value_via_display {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl $crate::ToIniValue for $t {
+                fn to_ini_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_str {
+    ($($t:ty),*) => {
+        $(
+            impl FromIniValue for $t {
+                type Err = <$t as std::str::FromStr>::Err;
+
+                fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+                    value.parse()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_display {
+    ($($t:ty),*) => {
+        $(
+            impl ToIniValue for $t {
+                fn to_ini_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_str!(f32, f64, char, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6);
+impl_from_str!(NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize);
+impl_from_str!(NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize);
+
+impl_display!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+impl_display!(bool, char, str, String, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6);
+impl_display!(NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize);
+impl_display!(NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize);
+impl_display!(Box<str>, Rc<str>, Arc<str>, Cow<'_, str>);
+
+macro_rules! impl_shared_str {
+    ($($t:ty),*) => {
+        $(
+            impl FromIniValue for $t {
+                type Err = Infallible;
+
+                fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+                    Ok(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_shared_str!(Box<str>, Rc<str>, Arc<str>);
+
+impl FromIniValue for Cow<'_, str> {
+    type Err = Infallible;
+
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+        Ok(Cow::Owned(value.to_owned()))
+    }
+}
+
+impl FromIniValue for String {
+    type Err = Infallible;
+
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+        Ok(value.to_owned())
+    }
+}
+
+impl FromIniValue for PathBuf {
+    type Err = Infallible;
+
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+        Ok(PathBuf::from(value))
+    }
+}
+
+impl ToIniValue for Path {
+    fn to_ini_value(&self) -> String {
+        self.display().to_string()
+    }
+}
+
+impl ToIniValue for PathBuf {
+    fn to_ini_value(&self) -> String {
+        self.display().to_string()
+    }
+}
+
+/// Booleans accept `true`/`false`, `yes`/`no`, `on`/`off` and `1`/`0` in any case
+impl FromIniValue for bool {
+    type Err = ValueError;
+
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(true),
+            "false" | "no" | "off" | "0" => Ok(false),
+            _ => Err(ValueError::new("expected one of true/false, yes/no, on/off, 1/0")),
+        }
+    }
+}
+
+/// Empty value is read as [None]
+impl<T> FromIniValue for Option<T>
+where
+    T: FromIniValue,
+{
+    type Err = T::Err;
+
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+        if value.is_empty() {
+            Ok(None)
+        } else {
+            T::from_ini_value(value).map(Some)
+        }
+    }
+}
+
+/// [None] is written as empty value
+impl<T> ToIniValue for Option<T>
+where
+    T: ToIniValue,
+{
+    fn to_ini_value(&self) -> String {
+        match self {
+            Some(value) => value.to_ini_value(),
+            None => String::new(),
+        }
+    }
+}
+
+/// Vectors use `,` as separator, like [Ini::get_vec](crate::Ini::get_vec)
+impl<T> FromIniValue for Vec<T>
+where
+    T: FromIniValue,
+{
+    type Err = ValueError;
+
+    fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+        parse_list(value, ",")
+    }
+}
+
+/// Vectors are written with `, ` as separator, like [Ini::item_vec](crate::Ini::item_vec)
+impl<T> ToIniValue for [T]
+where
+    T: ToIniValue,
+{
+    fn to_ini_value(&self) -> String {
+        join_list(self, ", ")
+    }
+}
+
+impl<T> ToIniValue for Vec<T>
+where
+    T: ToIniValue,
+{
+    fn to_ini_value(&self) -> String {
+        self.as_slice().to_ini_value()
+    }
+}
+
+impl<T> ToIniValue for &T
+where
+    T: ToIniValue + ?Sized,
+{
+    fn to_ini_value(&self) -> String {
+        (**self).to_ini_value()
+    }
+}
+
+macro_rules! impl_tuple {
+    ($len:expr; $($name:ident),*) => {
+        /// Tuples are comma-separated lists with fixed number of elements
+        impl<$($name),*> FromIniValue for ($($name,)*)
+        where
+            $($name: FromIniValue),*
+        {
+            type Err = ValueError;
+
+            fn from_ini_value(value: &str) -> Result<Self, Self::Err> {
+                let parts = list::split(value, ",")?;
+                if parts.len() != $len {
+                    return Err(ValueError::new(format!("expected {} elements, found {}", $len, parts.len())));
+                }
+                let mut parts = parts.into_iter();
+                Ok(($(
+                    $name::from_ini_value(&parts.next().unwrap_or_default())
+                        .map_err(|e| ValueError::new(e.to_string()))?,
+                )*))
+            }
+        }
+
+        impl<$($name),*> ToIniValue for ($($name,)*)
+        where
+            $($name: ToIniValue),*
+        {
+            #[allow(non_snake_case)]
+            fn to_ini_value(&self) -> String {
+                let ($($name,)*) = self;
+                list::join([$($name.to_ini_value()),*], ", ")
+            }
+        }
+    };
+}
+
+impl_tuple!(2; A, B);
+impl_tuple!(3; A, B, C);
+impl_tuple!(4; A, B, C, D);
+
+/// Parse list of values separated by `sep`, see [list](crate::list) grammar
+pub(crate) fn parse_list<T>(value: &str, sep: &str) -> Result<Vec<T>, ValueError>
+where
+    T: FromIniValue,
+{
+    list::split(value, sep)?
+        .iter()
+        .map(|s| T::from_ini_value(s).map_err(|e| ValueError::new(format!("element `{}`: {}", s, e))))
+        .collect()
+}
+
+/// Join list of values with `sep`, see [list](crate::list) grammar
+pub(crate) fn join_list<T>(values: &[T], sep: &str) -> String
+where
+    T: ToIniValue,
+{
+    list::join(values.iter().map(ToIniValue::to_ini_value), sep)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flexible_bool() {
+        for value in ["true", "Yes", "ON", "1"] {
+            assert_eq!(bool::from_ini_value(value).ok(), Some(true));
+        }
+        for value in ["false", "No", "off", "0"] {
+            assert_eq!(bool::from_ini_value(value).ok(), Some(false));
+        }
+        assert!(bool::from_ini_value("maybe").is_err());
+    }
+
+    #[test]
+    fn option() {
+        assert_eq!(Option::<u8>::from_ini_value("").ok(), Some(None));
+        assert_eq!(Option::<u8>::from_ini_value("4").ok(), Some(Some(4)));
+        assert!(Option::<u8>::from_ini_value("x").is_err());
+        assert_eq!(Some(4).to_ini_value(), "4");
+        assert_eq!(None::<u8>.to_ini_value(), "");
+    }
+
+    #[test]
+    fn tuple() {
+        assert_eq!(<(u8, String)>::from_ini_value("1, a").ok(), Some((1, "a".to_owned())));
+        assert!(<(u8, u8)>::from_ini_value("1, 2, 3").is_err());
+        assert_eq!((1, "a", 2.5).to_ini_value(), "1, a, 2.5");
+    }
+
+    #[test]
+    fn std_types() {
+        assert_eq!(NonZeroU32::from_ini_value("7").ok(), NonZeroU32::new(7));
+        assert!(NonZeroU32::from_ini_value("0").is_err());
+        assert_eq!(Rc::<str>::from_ini_value("text").ok().as_deref(), Some("text"));
+        assert_eq!(Cow::<str>::from_ini_value("text").ok(), Some(Cow::Borrowed("text")));
+        assert_eq!(Box::<str>::from("text").to_ini_value(), "text");
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Legacy(u8);
+
+    impl std::str::FromStr for Legacy {
+        type Err = std::num::ParseIntError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            s.parse().map(Legacy)
+        }
+    }
+
+    impl fmt::Display for Legacy {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "legacy {}", self.0)
+        }
+    }
+
+    impl_ini_value_via_from_str!(Legacy);
+    impl_ini_value_via_display!(Legacy);
+
+    #[test]
+    fn via_std_traits() {
+        assert_eq!(Legacy::from_ini_value("4").ok(), Some(Legacy(4)));
+        let error = Legacy::from_ini_value("x").err().map(|e| e.to_string());
+        // error of `FromStr` is kept
+        assert_eq!(error.as_deref(), Some("invalid digit found in string"));
+        assert_eq!(Legacy(4).to_ini_value(), "legacy 4");
+    }
+
+    #[test]
+    fn addresses() {
+        let ip = IpAddr::from_ini_value("127.0.0.1").ok();
+        assert_eq!(ip, Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        let socket = SocketAddr::from_ini_value("[::1]:80").ok();
+        assert_eq!(socket.map(|s| s.port()), Some(80));
+    }
+}
@@ -0,0 +1,300 @@
+//! Writer module
+//!
+//! Contains [WriteOptions] for output formats, `write` routine to format [Ini] with them
+//! and `write_io`/`write_buffered` routines to stream formatted [Ini] into [io::Write]
+use crate::{array_items, Ini, Section};
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{self, BufWriter, Write};
+
+/// Options which change how ini files are written
+///
+/// Defaults match [Display](Ini#impl-Display) output: `key = value` lines with LF line endings,
+/// one blank line between sections and trailing newline.
+///
+/// # Example
+/// ```
+/// # use tini::{Ini, WriteOptions};
+/// let conf = Ini::new().section("Service").item("Type", "simple").item("ExecStart", "/usr/bin/app");
+/// let options = WriteOptions::new().delimiter("=");
+///
+/// assert_eq!(conf.to_string_with(&options), "[Service]\nType=simple\nExecStart=/usr/bin/app\n");
+/// ```
+#[derive(Debug, Clone)]
+pub struct WriteOptions {
+    pub(crate) delimiter: String,
+    pub(crate) align: bool,
+    pub(crate) blank_lines: usize,
+    pub(crate) line_ending: String,
+    pub(crate) trailing_newline: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        WriteOptions {
+            delimiter: " = ".to_owned(),
+            align: false,
+            blank_lines: 1,
+            line_ending: "\n".to_owned(),
+            trailing_newline: true,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Create default options (similar to [WriteOptions::default])
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// String between key and value, `" = "` by default
+    pub fn delimiter(mut self, delimiter: &str) -> Self {
+        self.delimiter = delimiter.to_owned();
+        self
+    }
+
+    /// Pad keys so delimiters and values of every section are aligned into columns
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, WriteOptions};
+    /// let conf = Ini::new().section("db").item("host", "localhost").item("port", 5432).item("timeout", 30);
+    ///
+    /// assert_eq!(
+    ///     conf.to_string_with(&WriteOptions::new().align(true)),
+    ///     "[db]\nhost    = localhost\nport    = 5432\ntimeout = 30\n"
+    /// );
+    /// ```
+    pub fn align(mut self, enable: bool) -> Self {
+        self.align = enable;
+        self
+    }
+
+    /// Number of blank lines between sections, `1` by default
+    ///
+    /// Header comment is always followed by one blank line, so it is read back as header.
+    pub fn blank_lines(mut self, count: usize) -> Self {
+        self.blank_lines = count;
+        self
+    }
+
+    /// Line ending, `"\n"` by default
+    ///
+    /// # Example
+    /// ```
+    /// # use tini::{Ini, WriteOptions};
+    /// let conf = Ini::new().section("a").item("x", 1).section("b").item("y", 2);
+    /// let options = WriteOptions::new().line_ending("\r\n").blank_lines(0).trailing_newline(false);
+    ///
+    /// assert_eq!(conf.to_string_with(&options), "[a]\r\nx = 1\r\n[b]\r\ny = 2");
+    /// ```
+    pub fn line_ending(mut self, ending: &str) -> Self {
+        self.line_ending = ending.to_owned();
+        self
+    }
+
+    /// Write line ending after the last line, `true` by default
+    pub fn trailing_newline(mut self, enable: bool) -> Self {
+        self.trailing_newline = enable;
+        self
+    }
+}
+
+/// Sink which puts line endings between lines
+struct Lines<'a, W> {
+    out: &'a mut W,
+    ending: &'a str,
+    empty: bool,
+}
+
+impl<'a, W: fmt::Write> Lines<'a, W> {
+    fn push(&mut self, line: fmt::Arguments) -> fmt::Result {
+        if !self.empty {
+            self.out.write_str(self.ending)?;
+        }
+        self.empty = false;
+        self.out.write_fmt(line)
+    }
+
+    fn comment(&mut self, prefix: &str, text: &str) -> fmt::Result {
+        for line in text.lines() {
+            match line.is_empty() {
+                true => self.push(format_args!("{}", prefix))?,
+                false => self.push(format_args!("{} {}", prefix, line))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Key-value lines of `key`, php-style arrays are split into elements
+fn elements<'a>(section: &Section, key: &'a str, value: &'a str) -> Vec<(Cow<'a, str>, Cow<'a, str>)> {
+    match section.arrays.get(key).and_then(|&kind| array_items(kind, value)) {
+        Some(array) => array.into_iter().map(|(i, v)| (format!("{}[{}]", key, i).into(), v.into())).collect(),
+        None => vec![(key.into(), value.into())],
+    }
+}
+
+/// Format `ini` into `out` using `options`
+pub fn write<W: fmt::Write>(ini: &Ini, out: &mut W, options: &WriteOptions) -> fmt::Result {
+    let prefix = ini.comment_prefix.as_str();
+    let mut lines = Lines { out, ending: &options.line_ending, empty: true };
+    if let Some(header) = &ini.header {
+        lines.comment(prefix, header)?;
+    }
+    for (index, (name, section)) in ini.iter().enumerate() {
+        // header is always separated from the first section
+        let blank_lines = match (index, lines.empty) {
+            (_, true) => 0,
+            (0, false) => 1,
+            _ => options.blank_lines,
+        };
+        for _ in 0..blank_lines {
+            lines.push(format_args!(""))?;
+        }
+        if let Some(comment) = section.get_comment() {
+            lines.comment(prefix, comment)?;
+        }
+        lines.push(format_args!("[{}]", name))?;
+        let width = match options.align {
+            true => section
+                .iter()
+                .flat_map(|(key, value)| elements(section, key, value))
+                .map(|(key, _)| key.chars().count())
+                .max()
+                .unwrap_or(0),
+            false => 0,
+        };
+        for (key, value) in section.iter() {
+            if let Some(comment) = section.get_key_comment(key) {
+                lines.comment(prefix, comment)?;
+            }
+            for (key, value) in elements(section, key, value) {
+                lines.push(format_args!("{:width$}{}{}", key, options.delimiter, value, width = width))?;
+            }
+        }
+    }
+    if let Some(footer) = &ini.footer {
+        if !lines.empty {
+            lines.push(format_args!(""))?;
+        }
+        lines.comment(prefix, footer)?;
+    }
+    if !lines.empty && options.trailing_newline {
+        lines.out.write_str(lines.ending)?;
+    }
+    Ok(())
+}
+
+/// Adapter which passes formatted output to [io::Write] and keeps the error
+struct IoWriter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Stream `ini` into `writer` using `options`, writing stops at the first IO error
+pub fn write_io<W: io::Write>(ini: &Ini, writer: W, options: &WriteOptions) -> io::Result<()> {
+    let mut out = IoWriter { inner: writer, error: None };
+    write(ini, &mut out, options)
+        .map_err(|_| out.error.unwrap_or_else(|| io::Error::new(io::ErrorKind::Other, "formatter error")))
+}
+
+/// Stream `ini` into `writer` through a buffer, on error the rest of buffer is discarded
+pub fn write_buffered<W: io::Write>(ini: &Ini, writer: W, options: &WriteOptions) -> io::Result<()> {
+    let mut buffered = BufWriter::new(writer);
+    let result = write_io(ini, &mut buffered, options).and_then(|()| buffered.flush());
+    // dropped `BufWriter` would retry writing the buffer into the failed writer
+    let _ = buffered.into_parts();
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Error, ParseOptions};
+
+    #[test]
+    fn options() -> Result<(), Error> {
+        let text = "; header\n\n[php]\next[] = gd\next[] = curl\n; long key\nmemory_limit = 128M\n[b]\nx = 1\n";
+        let ini = Ini::from_string_with(text, &ParseOptions::new().php_arrays(true))?;
+        let options = WriteOptions::new().align(true).blank_lines(0).line_ending("\r\n");
+        let expected = [
+            "; header",
+            "",
+            "[php]",
+            "ext[]        = gd",
+            "ext[]        = curl",
+            "; long key",
+            "memory_limit = 128M",
+            "[b]",
+            "x = 1",
+            "",
+        ];
+        assert_eq!(ini.to_string_with(&options), expected.join("\r\n"));
+        assert_eq!(Ini::new().to_string_with(&options), "");
+        Ok(())
+    }
+
+    /// Writer which accepts `limit` bytes and fails after that
+    struct Limited {
+        limit: usize,
+        calls: usize,
+        failed: bool,
+        /// calls after the first error
+        late_calls: usize,
+    }
+
+    impl Limited {
+        fn new(limit: usize) -> Self {
+            Limited { limit, calls: 0, failed: false, late_calls: 0 }
+        }
+    }
+
+    impl io::Write for Limited {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.calls += 1;
+            self.late_calls += self.failed as usize;
+            if buf.len() > self.limit {
+                self.failed = true;
+                return Err(io::Error::new(io::ErrorKind::WriteZero, "full"));
+            }
+            self.limit -= buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn io_error() {
+        let ini = Ini::new().section("a").item("x", 1).section("b").item("y", 2);
+        let mut writer = Limited::new(usize::MAX);
+        write_io(&ini, &mut writer, &WriteOptions::default()).unwrap();
+        let total = writer.calls;
+        // writing stops at the first error
+        let mut writer = Limited::new(8);
+        let error = write_io(&ini, &mut writer, &WriteOptions::default()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+        assert!(writer.calls < total);
+        assert_eq!(writer.late_calls, 0);
+        // buffered output fails on flush and in the middle of document
+        let large = (0..10000).fold(Ini::new().section("a"), |ini, i| ini.item(format!("key{}", i), i));
+        for (ini, limit) in [(&ini, 8), (&large, 8), (&large, 20000)] {
+            let mut writer = Limited::new(limit);
+            let error = ini.to_writer(&mut writer).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+            assert_eq!(writer.late_calls, 0);
+        }
+    }
+}